@@ -1,143 +1,634 @@
 extern crate reqwest;
-use crate::{http::get, network::Network};
-use chrono::{DateTime, TimeZone, Utc};
-use regex::Captures;
-use url::Url;
+use crate::{error::Error, http::get, profile::ChainProfile, rest};
+use chrono::{DateTime, Duration, Utc};
+use num_format::{Locale, ToFormattedString};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{fmt, str::FromStr};
 
-pub const N_BLOCKS: u64 = 50_000;
+/// Numbers of blocks before the latest block, over which the block time is sampled.
+pub const SAMPLE_WINDOWS: [u64; 4] = [1_000, 10_000, 50_000, 200_000];
 
-/// Represents a block from the Evmos network.
-#[derive(Debug)]
+/// Maximum relative deviation of a sampled block time from the median of all samples,
+/// before the sample is considered an outlier.
+pub const MAX_BLOCK_TIME_DEVIATION: f64 = 0.25;
+
+/// Number of segments, into which the largest sampling window is split to estimate the block time variance.
+pub const N_SEGMENTS: u64 = 10;
+
+/// Confidence level of the estimated upgrade time interval.
+pub const CONFIDENCE_LEVEL: f64 = 0.9;
+
+/// Quantile of the standard normal distribution for the two-sided [`CONFIDENCE_LEVEL`].
+const CONFIDENCE_Z: f64 = 1.645;
+
+/// Represents a block of the chain.
+#[derive(Clone, Debug)]
 pub struct Block {
-    height: u64,
-    time: DateTime<Utc>,
+    pub height: u64,
+    pub time: DateTime<Utc>,
+    pub chain_id: String,
+    /// Address of the block proposer, which is not contained in the status of a node.
+    pub proposer: Option<String>,
 }
 
-/// Gets the estimated block height for the given upgrade time.
-pub async fn get_estimated_height(network: Network, upgrade_time: DateTime<Utc>) -> u64 {
-    let block = get_latest_block(network).await;
-    let block_minus_n = get_block(network, block.height - N_BLOCKS).await;
-    let seconds_per_block: f32 =
-        (block.time - block_minus_n.time).num_seconds() as f32 / N_BLOCKS as f32;
+/// Source of the block data of a network.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(try_from = "String")]
+pub enum BlockSource {
+    /// Cosmos SDK REST gateway (`cosmos/base/tendermint/v1beta1/blocks`).
+    #[default]
+    Rest,
+    /// CometBFT RPC of a node (`/status` and `/block?height=`).
+    Rpc,
+}
+
+impl fmt::Display for BlockSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BlockSource::Rest => write!(f, "rest"),
+            BlockSource::Rpc => write!(f, "rpc"),
+        }
+    }
+}
+
+impl FromStr for BlockSource {
+    type Err = String;
+
+    /// Parses the block source from the given string, e.g. when passed as a command line flag.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rest" => Ok(BlockSource::Rest),
+            "rpc" | "cometbft" | "tendermint" => Ok(BlockSource::Rpc),
+            _ => Err(format!(
+                "invalid block source: {}; expected one of rest, rpc",
+                s
+            )),
+        }
+    }
+}
 
-    let seconds_to_upgrade = (upgrade_time - block.time).num_seconds() as f32;
-    let blocks_to_upgrade = (seconds_to_upgrade / seconds_per_block) as u64;
+impl TryFrom<String> for BlockSource {
+    type Error = String;
 
-    blocks_to_upgrade + block.height
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Estimate of the upgrade height together with the block time it is based on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeightEstimate {
+    pub height: u64,
+    pub block_time: BlockTimeEstimate,
+    /// Interval, in which the estimated height is reached with the [`CONFIDENCE_LEVEL`].
+    /// It is not available if the block time variance could not be estimated.
+    pub time_interval: Option<ConfidenceInterval>,
+}
+
+/// Estimate of the time, at which a block height is reached, together with the block time it is based on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeEstimate {
+    pub time: DateTime<Utc>,
+    pub block_time: BlockTimeEstimate,
+    /// Interval, in which the block height is reached with the [`CONFIDENCE_LEVEL`].
+    pub time_interval: Option<ConfidenceInterval>,
 }
 
-/// Gets the latest block from the Evmos network.
-async fn get_latest_block(network: Network) -> Block {
-    let url = get_url(network, "cosmos/base/tendermint/v1beta1/blocks/latest").unwrap();
-    let response = get(url)
-        .await
-        .expect("the latest block should be successfully queried");
+/// Time interval, in which a block height is reached with the [`CONFIDENCE_LEVEL`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfidenceInterval {
+    pub earliest: DateTime<Utc>,
+    pub latest: DateTime<Utc>,
+}
 
-    process_block_body(response.text().await.unwrap())
+/// Average block time over a window of blocks before the latest block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowSample {
+    pub n_blocks: u64,
+    pub seconds_per_block: f64,
 }
 
-/// Builds the URL for the given REST endpoint.
-fn get_url(network: Network, endpoint: &str) -> Result<Url, url::ParseError> {
-    let base_url = get_rest_provider(network);
-    base_url.join(endpoint)
+/// Block time, that is estimated from the samples of multiple windows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockTimeEstimate {
+    pub seconds_per_block: f64,
+    /// Samples, that the estimate is based on.
+    pub samples: Vec<WindowSample>,
+    /// Samples, that were excluded as outliers, e.g. because of a chain halt.
+    pub outliers: Vec<WindowSample>,
 }
 
-/// Gets the block at the given height from the Evmos network.
-async fn get_block(network: Network, height: u64) -> Block {
-    // Combine the REST endpoint with the block height
-    let base_url = get_rest_provider(network);
-    let blocks_endpoint = "cosmos/base/tendermint/v1beta1/blocks/";
-    let url = base_url
-        .join(blocks_endpoint)
-        .expect("the blocks endpoint should be valid")
-        .join(height.to_string().as_str())
-        .expect("the blocks endpoint should be valid");
+impl BlockTimeEstimate {
+    /// Describes the estimation method, e.g. to be included in the proposal.
+    pub fn method(&self) -> String {
+        let method = match self.samples.len() {
+            1 => "the average block time",
+            _ => "the median block time",
+        };
+        let mut description = format!(
+            "{} of {:.2} seconds over the last {} blocks",
+            method,
+            self.seconds_per_block,
+            format_windows(&self.samples)
+        );
 
-    let response = get(url)
-        .await
-        .expect("the block should be successfully queried");
+        if !self.outliers.is_empty() {
+            description.push_str(
+                format!(
+                    " (excluding the last {} blocks as outliers)",
+                    format_windows(&self.outliers)
+                )
+                .as_str(),
+            );
+        }
 
-    process_block_body(response.text().await.unwrap())
+        description
+    }
 }
 
-/// Returns the appropriate REST provider for the given network.
-fn get_rest_provider(network: Network) -> Url {
-    let base_url: &str;
-    match network {
-        Network::LocalNode => base_url = "http://localhost:1317",
-        Network::Mainnet => base_url = "https://rest.evmos.lava.build",
-        Network::Testnet => base_url = "https://rest.evmos-testnet.lava.build",
+/// Gets the estimated block height for the given upgrade time.
+/// The block time is sampled over multiple windows before the latest block (see [`SAMPLE_WINDOWS`]),
+/// so that a chain halt or a period of slow blocks does not skew the estimate.
+pub async fn get_estimated_height(
+    profile: &ChainProfile,
+    upgrade_time: DateTime<Utc>,
+) -> Result<HeightEstimate, Error> {
+    let sampled = sample_block_time(profile).await?;
+    let seconds_to_upgrade = get_seconds_between(sampled.latest.time, upgrade_time);
+    let blocks_to_upgrade = (seconds_to_upgrade / sampled.block_time.seconds_per_block) as u64;
+
+    Ok(HeightEstimate {
+        height: blocks_to_upgrade + sampled.latest.height,
+        time_interval: sampled.get_time_interval(blocks_to_upgrade),
+        block_time: sampled.block_time,
+    })
+}
+
+/// Gets the estimated time, at which the given block height is reached.
+/// This is the inverse of [`get_estimated_height`] and uses the same block time sampling.
+pub async fn get_estimated_time(
+    profile: &ChainProfile,
+    height: u64,
+) -> Result<TimeEstimate, Error> {
+    let sampled = sample_block_time(profile).await?;
+    let blocks_to_height = match height > sampled.latest.height {
+        true => height - sampled.latest.height,
+        false => {
+            return Err(Error::Estimation(format!(
+                "block height {} is not after the latest block height {}",
+                height, sampled.latest.height
+            )))
+        }
     };
 
-    Url::parse(base_url).unwrap()
+    Ok(TimeEstimate {
+        time: sampled.get_time_after(blocks_to_height),
+        time_interval: sampled.get_time_interval(blocks_to_height),
+        block_time: sampled.block_time,
+    })
+}
+
+/// Block time, that is sampled before the latest block of the chain.
+struct SampledBlockTime {
+    latest: Block,
+    block_time: BlockTimeEstimate,
+    /// Standard deviation of the time per block, if it could be estimated.
+    std_dev: Option<f64>,
+}
+
+impl SampledBlockTime {
+    /// Returns the expected time, after which the given number of blocks is produced.
+    fn get_time_after(&self, n_blocks: u64) -> DateTime<Utc> {
+        let seconds = n_blocks as f64 * self.block_time.seconds_per_block;
+        self.latest.time + Duration::milliseconds((seconds * 1000.0) as i64)
+    }
+
+    /// Returns the confidence interval for the time, after which the given number of blocks is produced.
+    fn get_time_interval(&self, n_blocks: u64) -> Option<ConfidenceInterval> {
+        self.std_dev.map(|std_dev| {
+            get_confidence_interval(self.get_time_after(n_blocks), n_blocks, std_dev)
+        })
+    }
+}
+
+/// Samples the block time over the windows before the latest block (see [`SAMPLE_WINDOWS`])
+/// and estimates its variance.
+async fn sample_block_time(profile: &ChainProfile) -> Result<SampledBlockTime, Error> {
+    let block = get_latest_block(profile).await?;
+
+    let mut samples: Vec<WindowSample> = Vec::new();
+    for n_blocks in SAMPLE_WINDOWS.into_iter().filter(|n| *n < block.height) {
+        // NOTE: pruned nodes do not serve the older blocks, so that the larger windows are skipped
+        match get_block(profile, block.height - n_blocks).await {
+            Ok(block_minus_n) => {
+                let sample = WindowSample {
+                    n_blocks,
+                    seconds_per_block: get_seconds_between(block_minus_n.time, block.time)
+                        / n_blocks as f64,
+                };
+                println!(
+                    "  average block time over the last {} blocks: {:.3} seconds",
+                    n_blocks.to_formatted_string(&Locale::en),
+                    sample.seconds_per_block
+                );
+                samples.push(sample);
+            }
+            Err(e) => println!(
+                "WARNING: failed to get the block {} blocks before the latest block: {}",
+                n_blocks, e
+            ),
+        }
+    }
+
+    let block_time = estimate_block_time(samples)?;
+
+    // NOTE: the variance is estimated from the segments of the largest window, that is not an outlier
+    let window = block_time
+        .samples
+        .iter()
+        .map(|sample| sample.n_blocks)
+        .max()
+        .unwrap_or_default();
+    let segment_blocks = get_segment_blocks(profile, &block, window).await;
+
+    Ok(SampledBlockTime {
+        std_dev: get_block_time_std_dev(&segment_blocks),
+        latest: block,
+        block_time,
+    })
+}
+
+/// Rounds the block height to the nearest multiple of the given granularity, e.g. 1,000.
+pub fn round_height(height: u64, granularity: u64) -> u64 {
+    match granularity {
+        0 => height,
+        _ => (height + granularity / 2) / granularity * granularity,
+    }
+}
+
+/// Gets the blocks at the boundaries of [`N_SEGMENTS`] equally sized segments of the given window
+/// before the latest block, which is the last of the returned blocks.
+/// If a block cannot be queried, only the latest block is returned.
+async fn get_segment_blocks(profile: &ChainProfile, latest: &Block, window: u64) -> Vec<Block> {
+    let segment_size = window / N_SEGMENTS;
+    let mut blocks: Vec<Block> = Vec::new();
+
+    if segment_size > 0 {
+        for i in (1..=N_SEGMENTS).rev() {
+            match get_block(profile, latest.height - i * segment_size).await {
+                Ok(block) => blocks.push(block),
+                Err(e) => {
+                    println!(
+                        "WARNING: failed to get the blocks to estimate the block time variance: {}",
+                        e
+                    );
+                    blocks.clear();
+                    break;
+                }
+            }
+        }
+    }
+
+    blocks.push(latest.clone());
+    blocks
+}
+
+/// Estimates the standard deviation of the time per block from the given blocks,
+/// which are the boundaries of equally sized segments in ascending order.
+/// The variance of the average block time of the segments is scaled by the segment size,
+/// so that it describes the variance of a single block time.
+fn get_block_time_std_dev(blocks: &[Block]) -> Option<f64> {
+    let segments: Vec<(u64, f64)> = blocks
+        .windows(2)
+        .map(|pair| {
+            let n_blocks = pair[1].height - pair[0].height;
+            let seconds = get_seconds_between(pair[0].time, pair[1].time);
+            (n_blocks, seconds / n_blocks as f64)
+        })
+        .collect();
+    if segments.len() < 2 {
+        return None;
+    }
+
+    let n = segments.len() as f64;
+    let mean = segments.iter().map(|(_, spb)| spb).sum::<f64>() / n;
+    let variance = segments
+        .iter()
+        .map(|(_, spb)| (spb - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0);
+    let segment_size = segments
+        .iter()
+        .map(|(n_blocks, _)| *n_blocks as f64)
+        .sum::<f64>()
+        / n;
+
+    Some((variance * segment_size).sqrt())
+}
+
+/// Returns the interval around the expected time, in which the given number of blocks
+/// is produced with the [`CONFIDENCE_LEVEL`], given the standard deviation of the time per block.
+fn get_confidence_interval(
+    expected_time: DateTime<Utc>,
+    n_blocks: u64,
+    std_dev: f64,
+) -> ConfidenceInterval {
+    let margin = CONFIDENCE_Z * std_dev * (n_blocks as f64).sqrt();
+    let margin = Duration::milliseconds((margin * 1000.0).round() as i64);
+
+    ConfidenceInterval {
+        earliest: expected_time - margin,
+        latest: expected_time + margin,
+    }
+}
+
+/// Estimates the block time from the given window samples.
+/// Samples deviating from the median of all samples by more than [`MAX_BLOCK_TIME_DEVIATION`]
+/// are excluded as outliers and the median of the remaining samples is used as the estimate.
+fn estimate_block_time(samples: Vec<WindowSample>) -> Result<BlockTimeEstimate, Error> {
+    let median_of_all = median(&samples).ok_or(Error::Estimation(
+        "no block time could be sampled".to_string(),
+    ))?;
+
+    let (mut kept, mut outliers): (Vec<WindowSample>, Vec<WindowSample>) =
+        samples.into_iter().partition(|sample| {
+            (sample.seconds_per_block - median_of_all).abs() / median_of_all
+                <= MAX_BLOCK_TIME_DEVIATION
+        });
+
+    // NOTE: if the samples are too spread out to tell the outliers, all of them are used
+    if kept.is_empty() {
+        kept = std::mem::take(&mut outliers);
+    }
+
+    Ok(BlockTimeEstimate {
+        seconds_per_block: median(&kept).unwrap_or(median_of_all),
+        samples: kept,
+        outliers,
+    })
 }
 
-/// Processes the block body.
-fn process_block_body(body: String) -> Block {
-    // build regex to find the block height
-    let re = regex::Regex::new(r#"height":"(\d+)","time":"([T0-9\-:]+)"#).unwrap();
+/// Returns the median block time of the given samples.
+fn median(samples: &[WindowSample]) -> Option<f64> {
+    let mut values: Vec<f64> = samples
+        .iter()
+        .map(|sample| sample.seconds_per_block)
+        .collect();
+    values.sort_by(f64::total_cmp);
 
-    let captures: Captures;
-    let captures_res = re.captures(&body);
-    match captures_res {
-        None => panic!("failed to parse block response body"),
-        Some(c) => captures = c,
+    let mid = values.len() / 2;
+    match values.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((values[mid - 1] + values[mid]) / 2.0),
+        _ => Some(values[mid]),
     }
+}
 
-    // Extract the block height
-    let captured_height = captures.get(1).map_or("", |m| m.as_str());
-    let parsed_height = captured_height.parse::<u64>();
-    let height: u64;
-    match parsed_height {
-        Ok(h) => height = h,
-        Err(_) => panic!("Could not parse block height"),
+/// Returns the seconds between the given times with microsecond precision.
+/// Durations, that overflow the microseconds, fall back to millisecond precision.
+fn get_seconds_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    let duration = to - from;
+    match duration.num_microseconds() {
+        Some(microseconds) => microseconds as f64 / 1_000_000.0,
+        None => duration.num_milliseconds() as f64 / 1000.0,
     }
+}
+
+/// Formats the window sizes of the samples as an enumeration, e.g. `1,000, 10,000 and 50,000`.
+fn format_windows(samples: &[WindowSample]) -> String {
+    let windows: Vec<String> = samples
+        .iter()
+        .map(|sample| sample.n_blocks.to_formatted_string(&Locale::en))
+        .collect();
+
+    match windows.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        Some((last, _)) => last.clone(),
+        None => String::new(),
+    }
+}
+
+/// Gets the latest block of the chain from the block source of the profile.
+pub(crate) async fn get_latest_block(profile: &ChainProfile) -> Result<Block, Error> {
+    match profile.block_source {
+        BlockSource::Rest => {
+            let body = rest::get(profile, rest::LATEST_BLOCK_ENDPOINT).await?;
+            process_block_body(body.as_str())
+        }
+        BlockSource::Rpc => {
+            let body = get_rpc(profile, "status").await?;
+            process_status_body(body.as_str())
+        }
+    }
+}
 
-    // Parse the block time
-    let captured_time = captures.get(2).map_or("", |m| m.as_str());
-    let time_format = "%Y-%m-%dT%H:%M:%S";
-    let time_res = chrono::NaiveDateTime::parse_from_str(captured_time, time_format);
-    let time: DateTime<Utc>;
-    match time_res {
-        Ok(t) => time = Utc.from_utc_datetime(&t),
-        Err(e) => panic!("Could not parse block time: {}", e),
+/// Gets the block at the given height of the chain from the block source of the profile.
+async fn get_block(profile: &ChainProfile, height: u64) -> Result<Block, Error> {
+    match profile.block_source {
+        BlockSource::Rest => {
+            let endpoint = format!("cosmos/base/tendermint/v1beta1/blocks/{}", height);
+            let body = rest::get(profile, endpoint.as_str()).await?;
+            process_block_body(body.as_str())
+        }
+        BlockSource::Rpc => {
+            let endpoint = format!("block?height={}", height);
+            let body = get_rpc(profile, endpoint.as_str()).await?;
+            process_rpc_block_body(body.as_str())
+        }
     }
+}
+
+/// Queries the given endpoint of the CometBFT RPC and returns the response body.
+async fn get_rpc(profile: &ChainProfile, endpoint: &str) -> Result<String, Error> {
+    let url = profile.rpc_url(endpoint)?;
+    let response = get(url).await?.error_for_status()?;
+
+    Ok(response.text().await?)
+}
+
+/// Response of the block queries of the REST API.
+/// Newer Cosmos SDK versions additionally return the block as `sdk_block`,
+/// where the proposer address is bech32 encoded.
+#[derive(Deserialize)]
+struct BlockResponse {
+    block: Option<BlockData>,
+    sdk_block: Option<BlockData>,
+    /// Error message of a failed query.
+    message: Option<String>,
+}
+
+/// Response of the CometBFT RPC, which wraps the result of the query.
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    message: String,
+    #[serde(default)]
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct BlockData {
+    header: Header,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    chain_id: String,
+    height: String,
+    time: DateTime<Utc>,
+    proposer_address: String,
+}
 
-    Block { height, time }
+/// Result of the status query of the CometBFT RPC.
+#[derive(Deserialize)]
+struct StatusResult {
+    node_info: NodeInfo,
+    sync_info: SyncInfo,
+}
+
+#[derive(Deserialize)]
+struct NodeInfo {
+    network: String,
+}
+
+#[derive(Deserialize)]
+struct SyncInfo {
+    latest_block_height: String,
+    latest_block_time: DateTime<Utc>,
+}
+
+/// Processes the response body of the CometBFT RPC status query,
+/// which contains the latest block of the node.
+fn process_status_body(body: &str) -> Result<Block, Error> {
+    let status: StatusResult = process_rpc_body(body, "status")?;
+
+    Ok(Block {
+        height: parse_height(status.sync_info.latest_block_height.as_str())?,
+        time: status.sync_info.latest_block_time,
+        chain_id: status.node_info.network,
+        proposer: None,
+    })
+}
+
+/// Processes the response body of the CometBFT RPC block query.
+fn process_rpc_block_body(body: &str) -> Result<Block, Error> {
+    let block: BlockResponse = process_rpc_body(body, "block")?;
+
+    get_block_from_response(block)
+}
+
+/// Processes the response body of the block queries of the REST API.
+pub(crate) fn process_block_body(body: &str) -> Result<Block, Error> {
+    let block: BlockResponse = serde_json::from_str(body)
+        .map_err(|e| Error::Parse(format!("block response body: {}", e)))?;
+
+    get_block_from_response(block)
+}
+
+/// Unwraps the result of the given CometBFT RPC response body.
+fn process_rpc_body<T: DeserializeOwned>(body: &str, query: &str) -> Result<T, Error> {
+    let response: RpcResponse<T> = serde_json::from_str(body)
+        .map_err(|e| Error::Parse(format!("{} response body: {}", query, e)))?;
+
+    match (response.result, response.error) {
+        (Some(result), _) => Ok(result),
+        (None, Some(error)) => Err(Error::Parse(format!(
+            "{} response: {} {}",
+            query, error.message, error.data
+        ))),
+        (None, None) => Err(Error::Parse(format!(
+            "{} response body: missing result",
+            query
+        ))),
+    }
+}
+
+/// Builds the block from the header of the response, preferring the `sdk_block`.
+fn get_block_from_response(response: BlockResponse) -> Result<Block, Error> {
+    let header = match (response.sdk_block, response.block, response.message) {
+        (Some(block), _, _) | (None, Some(block), _) => block.header,
+        (None, None, Some(message)) => {
+            return Err(Error::Parse(format!("block response: {}", message)))
+        }
+        (None, None, None) => {
+            return Err(Error::Parse(
+                "block response body: missing block".to_string(),
+            ))
+        }
+    };
+
+    Ok(Block {
+        height: parse_height(header.height.as_str())?,
+        time: header.time,
+        chain_id: header.chain_id,
+        proposer: Some(header.proposer_address),
+    })
+}
+
+/// Parses the block height, which is encoded as a string in the responses.
+fn parse_height(height: &str) -> Result<u64, Error> {
+    height
+        .parse::<u64>()
+        .map_err(|e| Error::Parse(format!("block height {}: {}", height, e)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::network::Network;
-    use chrono::{Days, TimeZone};
+    use chrono::{Days, Duration, SubsecRound, TimeZone};
+
+    fn profile(network: Network) -> ChainProfile {
+        ChainProfile::builtin(network)
+    }
 
     #[tokio::test]
     async fn test_get_estimated_height() {
         let now = Utc::now();
         let upgrade_time = now.checked_add_days(Days::new(5)).unwrap();
-        let height = get_estimated_height(Network::Mainnet, upgrade_time).await;
-        assert!(height > 16705125, "expected a different block height");
+        let estimate = get_estimated_height(&profile(Network::Mainnet), upgrade_time)
+            .await
+            .expect("the height should be estimated");
+        assert!(
+            estimate.height > 16705125,
+            "expected a different block height"
+        );
     }
 
     #[tokio::test]
     async fn test_get_latest_block_mainnet() {
-        let block = get_latest_block(Network::Mainnet).await;
+        let block = get_latest_block(&profile(Network::Mainnet)).await.unwrap();
+        assert!(block.height > 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_block_mainnet_rpc() {
+        let mut profile = profile(Network::Mainnet);
+        profile.block_source = BlockSource::Rpc;
+
+        let block = get_latest_block(&profile).await.unwrap();
         assert!(block.height > 0);
     }
 
     #[tokio::test]
     async fn test_get_latest_block_testnet() {
-        let block = get_latest_block(Network::Testnet).await;
+        let block = get_latest_block(&profile(Network::Testnet)).await.unwrap();
         assert!(block.height > 0);
     }
 
     #[tokio::test]
     async fn test_get_block_mainnet() {
-        let block = get_block(Network::Mainnet, 16705125).await;
+        let block = get_block(&profile(Network::Mainnet), 16705125)
+            .await
+            .unwrap();
         assert_eq!(block.height, 16705125, "expected a different block height");
         assert_eq!(
-            block.time,
+            block.time.trunc_subsecs(0),
             Utc.with_ymd_and_hms(2023, 10, 25, 17, 21, 50).unwrap(),
             "expected a different block time",
         );
@@ -145,10 +636,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_block_testnet() {
-        let block = get_block(Network::Testnet, 18182953).await;
+        let block = get_block(&profile(Network::Testnet), 18182953)
+            .await
+            .unwrap();
         assert_eq!(block.height, 18182953, "expected a different block height");
         assert_eq!(
-            block.time,
+            block.time.trunc_subsecs(0),
             Utc.with_ymd_and_hms(2023, 10, 25, 17, 22, 23).unwrap(),
             "expected a different block time",
         );
@@ -156,14 +649,284 @@ mod tests {
 
     #[test]
     fn test_process_block_body_pass() {
-        let body = r#"{"block_id":{"hash":"CDHpDYu4tRibegIDTHust45sWB6ebnNE0Wq4sMpbSP8=","part_set_header":{"total":1,"hash":"bLAKlbU5Y0rqC1p07Xuhxm355sa+wPxwD9roDtnIzqA="}},"block":{"header":{"version":{"block":"11","app":"0"},"chain_id":"evmos_9001-2","height":"16699401","time":"2023-10-25T10:09:34.440526177Z","last_block_id""#;
-        let block = process_block_body(body.to_string());
+        let body = r#"{"block_id":{"hash":"CDHpDYu4tRibegIDTHust45sWB6ebnNE0Wq4sMpbSP8=","part_set_header":{"total":1,"hash":"bLAKlbU5Y0rqC1p07Xuhxm355sa+wPxwD9roDtnIzqA="}},"block":{"header":{"version":{"block":"11","app":"0"},"chain_id":"evmos_9001-2","height":"16699401","time":"2023-10-25T10:09:34.440526177Z","proposer_address":"Y2bVwiYzbUxEEC5BUNsQAJnjm54="},"data":{"txs":[]}}}"#;
+        let block = process_block_body(body).unwrap();
+
+        assert_eq!(block.height, 16699401, "expected a different block height");
+        assert_eq!(
+            block.time,
+            Utc.with_ymd_and_hms(2023, 10, 25, 10, 9, 34).unwrap()
+                + Duration::nanoseconds(440526177),
+            "expected a different block time",
+        );
+        assert_eq!(block.chain_id, "evmos_9001-2");
+        assert_eq!(
+            block.proposer.as_deref(),
+            Some("Y2bVwiYzbUxEEC5BUNsQAJnjm54=")
+        );
+    }
+
+    #[test]
+    fn test_process_block_body_sdk_block() {
+        let body = r#"{"block_id":{},"block":{"header":{"chain_id":"evmos_9001-2","height":"16699401","time":"2023-10-25T10:09:34.440526177Z","proposer_address":"Y2bVwiYzbUxEEC5BUNsQAJnjm54="}},"sdk_block":{"header":{"chain_id":"evmos_9001-2","height":"16699401","time":"2023-10-25T10:09:34.440526177Z","proposer_address":"evmosvalcons1vvmdts3xxdk5c3qs9eq4pkcsqzv78xu7tzkdvy"}}}"#;
+        let block = process_block_body(body).unwrap();
 
         assert_eq!(block.height, 16699401, "expected a different block height");
+        assert_eq!(
+            block.proposer.as_deref(),
+            Some("evmosvalcons1vvmdts3xxdk5c3qs9eq4pkcsqzv78xu7tzkdvy"),
+            "expected the proposer of the sdk block"
+        );
+    }
+
+    #[test]
+    fn test_process_block_body_fail() {
+        let res = process_block_body(r#"{"code":3,"message":"height must be greater than 0"}"#);
+        assert!(
+            matches!(res, Err(Error::Parse(ref msg)) if msg.contains("height must be greater than 0")),
+            "expected the error message of the response, got {:?}",
+            res
+        );
+    }
+
+    #[test]
+    fn test_process_block_body_invalid_header() {
+        let body = r#"{"block":{"header":{"chain_id":"evmos_9001-2","height":"16699401","time":"yesterday","proposer_address":""}}}"#;
+        let res = process_block_body(body);
+        assert!(
+            matches!(res, Err(Error::Parse(ref msg)) if msg.contains("block response body")),
+            "expected a parse error for an invalid block time, got {:?}",
+            res
+        );
+    }
+
+    #[test]
+    fn test_process_rpc_block_body_pass() {
+        let body = r#"{"jsonrpc":"2.0","id":-1,"result":{"block_id":{"hash":"2A1F1E0B0A3E9C4F4C0E3E0A1E9E0F6D8C5A4B3C2D1E0F9A8B7C6D5E4F3A2B1C"},"block":{"header":{"version":{"block":"11"},"chain_id":"evmos_9001-2","height":"16705125","time":"2023-10-25T17:21:50.612287113Z","proposer_address":"6366D5C226336D4C44102E4150DB100099E39B9E"}}}}"#;
+        let block = process_rpc_block_body(body).unwrap();
+
+        assert_eq!(block.height, 16705125, "expected a different block height");
         assert_eq!(
             block.time,
-            Utc.with_ymd_and_hms(2023, 10, 25, 10, 09, 34).unwrap(),
+            Utc.with_ymd_and_hms(2023, 10, 25, 17, 21, 50).unwrap()
+                + Duration::nanoseconds(612287113),
             "expected a different block time",
         );
+        assert_eq!(
+            block.proposer.as_deref(),
+            Some("6366D5C226336D4C44102E4150DB100099E39B9E")
+        );
+    }
+
+    #[test]
+    fn test_process_rpc_block_body_fail() {
+        let res = process_rpc_block_body(
+            r#"{"jsonrpc":"2.0","id":-1,"error":{"code":-32603,"message":"Internal error","data":"height 99999999 must be less than or equal to the current blockchain height 16705125"}}"#,
+        );
+        assert!(
+            matches!(res, Err(Error::Parse(ref msg)) if msg.contains("must be less than or equal")),
+            "expected the error data of the response, got {:?}",
+            res
+        );
+    }
+
+    #[test]
+    fn test_process_status_body_pass() {
+        let body = r#"{"jsonrpc":"2.0","id":-1,"result":{"node_info":{"network":"evmos_9001-2"},"sync_info":{"latest_block_height":"16705125","latest_block_time":"2023-10-25T17:21:50Z","catching_up":false}}}"#;
+        let block = process_status_body(body).unwrap();
+
+        assert_eq!(block.height, 16705125, "expected a different block height");
+        assert_eq!(
+            block.time,
+            Utc.with_ymd_and_hms(2023, 10, 25, 17, 21, 50).unwrap(),
+            "expected a different block time",
+        );
+        assert_eq!(block.chain_id, "evmos_9001-2");
+        assert_eq!(block.proposer, None);
+    }
+
+    #[test]
+    fn test_process_status_body_fail() {
+        let res = process_status_body(
+            r#"{"jsonrpc":"2.0","id":-1,"error":{"code":-32603,"message":"Internal error"}}"#,
+        );
+        assert!(
+            matches!(res, Err(Error::Parse(_))),
+            "expected a parse error for an invalid status body",
+        );
+    }
+
+    fn sample(n_blocks: u64, seconds_per_block: f64) -> WindowSample {
+        WindowSample {
+            n_blocks,
+            seconds_per_block,
+        }
+    }
+
+    #[test]
+    fn test_estimate_block_time_pass() {
+        let estimate = estimate_block_time(vec![
+            sample(1_000, 2.1),
+            sample(10_000, 1.9),
+            sample(50_000, 2.0),
+            sample(200_000, 2.0),
+        ])
+        .unwrap();
+
+        assert_eq!(estimate.seconds_per_block, 2.0);
+        assert_eq!(estimate.samples.len(), 4);
+        assert!(estimate.outliers.is_empty());
+        assert_eq!(
+            estimate.method(),
+            "the median block time of 2.00 seconds over the last 1,000, 10,000, 50,000 and 200,000 blocks"
+        );
+    }
+
+    #[test]
+    fn test_estimate_block_time_halt() {
+        // NOTE: a chain halt within the last 10,000 blocks slows down the two shortest windows
+        let estimate = estimate_block_time(vec![
+            sample(1_000, 9.0),
+            sample(10_000, 2.8),
+            sample(50_000, 2.1),
+            sample(200_000, 2.0),
+        ])
+        .unwrap();
+
+        assert_eq!(estimate.seconds_per_block, 2.1);
+        assert_eq!(
+            estimate.outliers,
+            vec![sample(1_000, 9.0)],
+            "expected the halt to be excluded"
+        );
+        assert_eq!(
+            estimate.method(),
+            "the median block time of 2.10 seconds over the last 10,000, 50,000 and 200,000 blocks (excluding the last 1,000 blocks as outliers)"
+        );
+    }
+
+    #[test]
+    fn test_estimate_block_time_single_window() {
+        let estimate = estimate_block_time(vec![sample(1_000, 1.5)]).unwrap();
+        assert_eq!(estimate.seconds_per_block, 1.5);
+        assert_eq!(
+            estimate.method(),
+            "the average block time of 1.50 seconds over the last 1,000 blocks"
+        );
+    }
+
+    #[test]
+    fn test_estimate_block_time_no_samples() {
+        assert!(matches!(
+            estimate_block_time(vec![]),
+            Err(Error::Estimation(_))
+        ));
+    }
+
+    fn block(height: u64, seconds: i64) -> Block {
+        Block {
+            height,
+            time: Utc.with_ymd_and_hms(2023, 10, 25, 0, 0, 0).unwrap() + Duration::seconds(seconds),
+            chain_id: "evmos_9001-2".to_string(),
+            proposer: None,
+        }
+    }
+
+    #[test]
+    fn test_get_block_time_std_dev_constant() {
+        let blocks = vec![block(100, 0), block(200, 200), block(300, 400)];
+        assert_eq!(get_block_time_std_dev(&blocks), Some(0.0));
+    }
+
+    #[test]
+    fn test_get_block_time_std_dev_varying() {
+        // NOTE: the segments have average block times of 1 and 3 seconds,
+        // which is a variance of 2 for the segments and of 200 for a single block
+        let blocks = vec![block(100, 0), block(200, 100), block(300, 400)];
+        let std_dev = get_block_time_std_dev(&blocks).unwrap();
+        assert!(
+            (std_dev - 200f64.sqrt()).abs() < 1e-9,
+            "expected a different standard deviation: {}",
+            std_dev
+        );
+    }
+
+    #[test]
+    fn test_get_block_time_std_dev_single_segment() {
+        assert_eq!(
+            get_block_time_std_dev(&[block(100, 0), block(200, 200)]),
+            None
+        );
+        assert_eq!(get_block_time_std_dev(&[block(100, 0)]), None);
+    }
+
+    #[test]
+    fn test_sampled_block_time() {
+        let sampled = SampledBlockTime {
+            latest: block(16_705_125, 0),
+            block_time: estimate_block_time(vec![sample(1_000, 2.0)]).unwrap(),
+            std_dev: Some(0.1),
+        };
+
+        let time = sampled.get_time_after(1_800);
+        assert_eq!(
+            time,
+            block(0, 3_600).time,
+            "expected the time after one hour"
+        );
+
+        let interval = sampled
+            .get_time_interval(1_800)
+            .expect("expected an interval for a known variance");
+        assert!(interval.earliest < time && time < interval.latest);
+    }
+
+    #[tokio::test]
+    async fn test_get_estimated_time_past_height() {
+        let profile = profile(Network::Mainnet);
+        let err = get_estimated_time(&profile, 16_705_125)
+            .await
+            .expect_err("expected an error for a past block height");
+        assert!(matches!(err, Error::Estimation(_)));
+    }
+
+    #[test]
+    fn test_get_seconds_between() {
+        let from = Utc.with_ymd_and_hms(2023, 10, 23, 16, 0, 0).unwrap();
+        let to = from + Duration::microseconds(1_500_250);
+        assert_eq!(get_seconds_between(from, to), 1.50025);
+        assert_eq!(get_seconds_between(to, from), -1.50025);
+    }
+
+    #[test]
+    fn test_round_height() {
+        assert_eq!(round_height(16_800_499, 1_000), 16_800_000);
+        assert_eq!(round_height(16_800_500, 1_000), 16_801_000);
+        assert_eq!(round_height(16_834_567, 10_000), 16_830_000);
+        assert_eq!(round_height(16_834_567, 0), 16_834_567);
+    }
+
+    #[test]
+    fn test_get_confidence_interval() {
+        let expected_time = Utc.with_ymd_and_hms(2023, 10, 30, 16, 0, 0).unwrap();
+        let interval = get_confidence_interval(expected_time, 10_000, 0.1);
+
+        // NOTE: 1.645 * 0.1 * sqrt(10,000) = 16.45 seconds
+        assert_eq!(
+            interval.earliest,
+            expected_time - Duration::milliseconds(16_450)
+        );
+        assert_eq!(
+            interval.latest,
+            expected_time + Duration::milliseconds(16_450)
+        );
+    }
+
+    #[test]
+    fn test_block_source_from_str() {
+        assert_eq!("rest".parse::<BlockSource>(), Ok(BlockSource::Rest));
+        assert_eq!("RPC".parse::<BlockSource>(), Ok(BlockSource::Rpc));
+        assert_eq!("cometbft".parse::<BlockSource>(), Ok(BlockSource::Rpc));
+        assert!("grpc".parse::<BlockSource>().is_err());
     }
 }
@@ -0,0 +1,223 @@
+use crate::{error::Error, http::get, project::Repository, release::get_release, version::Version};
+use regex::Regex;
+use url::Url;
+
+/// Represents a section of the changelog, e.g. `Bug Fixes`, with its entries.
+#[derive(Debug, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub entries: Vec<String>,
+}
+
+/// Returns the features of the target version as a list of changelog sections.
+/// The changelog at the target tag is used and the release body serves as a fallback.
+pub async fn get_features(
+    repository: &Repository,
+    previous_version: &Version,
+    target_version: &Version,
+) -> Result<Vec<String>, Error> {
+    let sections = match get_changelog(repository, target_version).await {
+        Ok(changelog) => parse_changelog(changelog.as_str(), previous_version, target_version),
+        Err(e) => {
+            println!("WARNING: failed to get the changelog: {}", e);
+            Vec::new()
+        }
+    };
+
+    let sections = match sections.is_empty() {
+        true => {
+            let release = get_release(repository, target_version.to_string().as_str()).await?;
+            let body = release.body.unwrap_or_default();
+            parse_changelog(body.as_str(), previous_version, target_version)
+        }
+        false => sections,
+    };
+
+    Ok(sections.iter().map(format_section).collect())
+}
+
+/// Gets the contents of the changelog at the tag of the given version.
+async fn get_changelog(repository: &Repository, version: &Version) -> Result<String, Error> {
+    let url = Url::parse(repository.raw_file_url(version, "CHANGELOG.md").as_str())?;
+    let response = get(url).await?.error_for_status()?;
+
+    Ok(response.text().await?)
+}
+
+/// Parses the changelog and returns the entries of all versions after the previous version
+/// up to and including the target version, grouped by their sections.
+/// The `Unreleased` entries are only included for release candidates, since their changes
+/// are not yet listed under a version header.
+/// If the changelog does not contain any version headers (e.g. in a release body),
+/// all entries are returned.
+pub fn parse_changelog(
+    changelog: &str,
+    previous_version: &Version,
+    target_version: &Version,
+) -> Vec<Section> {
+    let version_header = Regex::new(r"^##\s+\[?(v\d+\.\d+\.\d+(-rc\d+)?|Unreleased)\]?").unwrap();
+    let has_version_headers = changelog.lines().any(|line| version_header.is_match(line));
+
+    let mut sections: Vec<Section> = Vec::new();
+    let mut included = !has_version_headers;
+    let mut current_section: Option<usize> = None;
+
+    for line in changelog.lines() {
+        if let Some(captures) = version_header.captures(line) {
+            included = match captures[1].parse::<Version>() {
+                Ok(version) => &version > previous_version && &version <= target_version,
+                Err(_) => target_version.is_release_candidate(),
+            };
+            current_section = None;
+            continue;
+        }
+
+        if !included {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix("### ") {
+            let name = name.trim();
+            current_section = match sections.iter().position(|section| section.name == name) {
+                Some(index) => Some(index),
+                None => {
+                    sections.push(Section {
+                        name: name.to_string(),
+                        entries: Vec::new(),
+                    });
+                    Some(sections.len() - 1)
+                }
+            };
+            continue;
+        }
+
+        let section = match current_section {
+            Some(index) => &mut sections[index],
+            None => continue,
+        };
+
+        // NOTE: indented lines continue the previous entry
+        if let Some(entry) = line.strip_prefix("- ").or(line.strip_prefix("* ")) {
+            section.entries.push(entry.trim().to_string());
+        } else if line.starts_with("  ") && !line.trim().is_empty() {
+            if let Some(entry) = section.entries.last_mut() {
+                entry.push(' ');
+                entry.push_str(line.trim());
+            }
+        }
+    }
+
+    sections.retain(|section| !section.entries.is_empty());
+    sections
+}
+
+/// Formats the section as a feature with the section name and the nested entries.
+fn format_section(section: &Section) -> String {
+    let entries = section
+        .entries
+        .iter()
+        .map(|entry| format!("  - {}", entry))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!("**{}**\n{}", section.name, entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "\
+# Changelog
+
+## Unreleased
+
+### Improvements
+
+- (evm) [#1900](https://github.com/evmos/evmos/pull/1900) Unreleased change.
+
+## [v15.0.0](https://github.com/evmos/evmos/releases/tag/v15.0.0) - 2023-10-31
+
+### State Machine Breaking
+
+- (vesting) [#1862](https://github.com/evmos/evmos/pull/1862) Add Authorization Grants
+  to the Vesting extension.
+- (evm) [#1860](https://github.com/evmos/evmos/pull/1860) Remove the claims module.
+
+### Bug Fixes
+
+- (evm) [#1850](https://github.com/evmos/evmos/pull/1850) Fix gas estimation.
+
+## [v14.1.0](https://github.com/evmos/evmos/releases/tag/v14.1.0) - 2023-10-10
+
+### Bug Fixes
+
+- (osmosis) [#1800](https://github.com/evmos/evmos/pull/1800) Fix outpost.
+
+## [v14.0.0](https://github.com/evmos/evmos/releases/tag/v14.0.0) - 2023-09-01
+
+### Features
+
+- (evm) [#1700](https://github.com/evmos/evmos/pull/1700) Previous feature.
+";
+
+    fn version(version: &str) -> Version {
+        version.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_changelog_between_versions() {
+        let sections = parse_changelog(CHANGELOG, &version("v14.0.0"), &version("v15.0.0"));
+        assert_eq!(
+            sections,
+            vec![
+                Section {
+                    name: "State Machine Breaking".to_string(),
+                    entries: vec![
+                        "(vesting) [#1862](https://github.com/evmos/evmos/pull/1862) Add Authorization Grants to the Vesting extension.".to_string(),
+                        "(evm) [#1860](https://github.com/evmos/evmos/pull/1860) Remove the claims module.".to_string(),
+                    ],
+                },
+                Section {
+                    name: "Bug Fixes".to_string(),
+                    entries: vec![
+                        "(evm) [#1850](https://github.com/evmos/evmos/pull/1850) Fix gas estimation.".to_string(),
+                        "(osmosis) [#1800](https://github.com/evmos/evmos/pull/1800) Fix outpost.".to_string(),
+                    ],
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn test_parse_changelog_release_candidate() {
+        let sections = parse_changelog(CHANGELOG, &version("v15.0.0"), &version("v16.0.0-rc1"));
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].name, "Improvements");
+    }
+
+    #[test]
+    fn test_parse_changelog_without_version_headers() {
+        let body = "### Features\n\n- first feature\n- second feature\n";
+        let sections = parse_changelog(body, &version("v14.0.0"), &version("v15.0.0"));
+        assert_eq!(
+            sections,
+            vec![Section {
+                name: "Features".to_string(),
+                entries: vec!["first feature".to_string(), "second feature".to_string()],
+            }],
+        );
+    }
+
+    #[test]
+    fn test_format_section() {
+        let section = Section {
+            name: "Bug Fixes".to_string(),
+            entries: vec!["first fix".to_string(), "second fix".to_string()],
+        };
+        assert_eq!(
+            format_section(&section),
+            "**Bug Fixes**\n  - first fix\n  - second fix"
+        );
+    }
+}
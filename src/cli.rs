@@ -0,0 +1,433 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand};
+use std::path::PathBuf;
+use upgrade_helper::{
+    block::BlockSource, gov::Coin, project::Repository, version::Version, Network,
+};
+
+/// Command line arguments for the upgrade helper.
+/// Every value that is not passed as a flag is queried interactively.
+#[derive(Debug, Parser)]
+#[command(author, version, about)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Upgrade configuration file (TOML), which replaces all interactive inputs.
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = ["network", "profile", "chain_registry", "previous_version", "target_version", "upgrade_date"]
+    )]
+    pub config: Option<PathBuf>,
+
+    /// Network to prepare the upgrade for (local-node, testnet, mainnet).
+    #[arg(short, long)]
+    pub network: Option<Network>,
+
+    /// Chain profile (TOML) describing the network of another Cosmos SDK chain,
+    /// which is used instead of the built-in Evmos networks.
+    /// A `chain.json` file in the cosmos/chain-registry schema is imported as well.
+    #[arg(long, conflicts_with = "network")]
+    pub profile: Option<PathBuf>,
+
+    /// Local checkout of the cosmos/chain-registry repository to load the chain from.
+    #[arg(long, conflicts_with_all = ["network", "profile"])]
+    pub chain_registry: Option<PathBuf>,
+
+    /// Name of the chain in the chain registry, e.g. evmos or evmostestnet.
+    #[arg(long, requires = "chain_registry")]
+    pub chain: Option<String>,
+
+    /// Version to upgrade from, e.g. v14.0.0.
+    #[arg(short, long)]
+    pub previous_version: Option<Version>,
+
+    /// Version to upgrade to, e.g. v15.0.0.
+    #[arg(short, long)]
+    pub target_version: Option<Version>,
+
+    /// Date of the planned upgrade (YYYY-MM-DD).
+    #[arg(short = 'd', long)]
+    pub upgrade_date: Option<NaiveDate>,
+
+    /// Time of the planned upgrade in UTC (HH:MM:SS).
+    #[arg(long, default_value = "16:00:00")]
+    pub upgrade_time: NaiveTime,
+
+    /// Deposit for the proposal, e.g. 2000000000000000000000aevmos.
+    /// Defaults to the minimum deposit of the network.
+    #[arg(long)]
+    pub deposit: Option<Coin>,
+
+    /// Only warn about conflicting upgrade plans instead of refusing to prepare the proposal.
+    #[arg(long)]
+    pub allow_conflicts: bool,
+
+    /// Submit the upgrade as an expedited proposal, which is supported by Cosmos SDK v0.50+.
+    #[arg(long)]
+    pub expedited: bool,
+
+    /// Author of the proposal, e.g. "Evmos Core Team".
+    #[arg(long)]
+    pub author: Option<String>,
+
+    /// GitHub repository of the chain (owner/name), which overrides the repository
+    /// of the chain profile, e.g. evmos/evmos.
+    #[arg(long)]
+    pub repository: Option<Repository>,
+
+    /// Source of the block data (rest, rpc), which overrides the block source
+    /// of the chain profile. `rpc` queries the CometBFT RPC of the node.
+    #[arg(long)]
+    pub block_source: Option<BlockSource>,
+
+    /// Proposal template (Handlebars) to use instead of the embedded default template.
+    #[arg(long, conflicts_with = "template_dir")]
+    pub template: Option<PathBuf>,
+
+    /// Directory containing proposal templates per network (e.g. `testnet.hbs`),
+    /// falling back to `proposal.hbs` in the directory. It is an error if none of them exists.
+    #[arg(long)]
+    pub template_dir: Option<PathBuf>,
+
+    /// Rounds the estimated upgrade height to a multiple of the given number of blocks, e.g. 1000.
+    #[arg(long)]
+    pub round_to: Option<u64>,
+
+    /// Maximum shift of the upgrade time in minutes, that is accepted when rounding the upgrade height.
+    /// Defaults to 30 minutes.
+    #[arg(long)]
+    pub max_drift_minutes: Option<i64>,
+
+    /// Block height, for which the time it is reached is estimated instead of preparing a proposal.
+    #[arg(
+        long,
+        conflicts_with_all = ["previous_version", "target_version", "upgrade_date"]
+    )]
+    pub height: Option<u64>,
+
+    /// Directory to write the generated proposal to.
+    #[arg(short, long, default_value = ".")]
+    pub output: PathBuf,
+}
+
+impl Args {
+    /// Checks the conflicts between the flags and the commands, which cannot be declared for clap.
+    pub fn check_conflicts(&self) -> Result<(), clap::Error> {
+        if self.height.is_some() && self.command.is_some() {
+            return Err(Args::command().error(
+                ErrorKind::ArgumentConflict,
+                "the argument '--height' cannot be used with a command",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Commands, that are run instead of preparing a proposal.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Periodically re-estimates the time of an already scheduled upgrade height
+    /// and exits with code 2 if it drifts too far from the announced time.
+    Watch(WatchArgs),
+}
+
+/// Command line arguments for the `watch` command.
+#[derive(Debug, clap::Args)]
+pub struct WatchArgs {
+    /// Scheduled upgrade height.
+    #[arg(long)]
+    pub height: u64,
+
+    /// Announced upgrade time (RFC 3339), e.g. 2023-11-06T16:00:00Z.
+    #[arg(long)]
+    pub announced_time: DateTime<Utc>,
+
+    /// Maximum deviation from the announced time in minutes.
+    #[arg(long, default_value_t = crate::DEFAULT_MAX_DRIFT_MINUTES, value_parser = clap::value_parser!(i64).range(0..))]
+    pub max_drift_minutes: i64,
+
+    /// Minutes between the re-estimations.
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(i64).range(1..))]
+    pub interval_minutes: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args_pass() {
+        let args = Args::try_parse_from([
+            "upgrade-helper",
+            "--network",
+            "testnet",
+            "--previous-version",
+            "v14.0.0",
+            "--target-version",
+            "v15.0.0-rc1",
+            "--upgrade-date",
+            "2023-10-31",
+        ])
+        .expect("the arguments should be parsed");
+
+        assert_eq!(args.network, Some(Network::Testnet));
+        assert_eq!(args.previous_version, Some("v14.0.0".parse().unwrap()));
+        assert_eq!(args.target_version, Some("v15.0.0-rc1".parse().unwrap()));
+        assert_eq!(
+            args.upgrade_date,
+            Some(NaiveDate::from_ymd_opt(2023, 10, 31).unwrap())
+        );
+        assert_eq!(
+            args.upgrade_time,
+            NaiveTime::from_hms_opt(16, 0, 0).unwrap()
+        );
+        assert_eq!(args.output, PathBuf::from("."));
+        assert_eq!(args.deposit, None);
+        assert!(!args.allow_conflicts);
+        assert!(!args.expedited);
+    }
+
+    #[test]
+    fn test_parse_args_deposit() {
+        let args = Args::try_parse_from(["upgrade-helper", "--deposit", "1000aevmos"])
+            .expect("the arguments should be parsed");
+        assert_eq!(args.deposit, Some("1000aevmos".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_args_expedited() {
+        let args = Args::try_parse_from(["upgrade-helper", "--expedited"])
+            .expect("the arguments should be parsed");
+        assert!(args.expedited);
+    }
+
+    #[test]
+    fn test_parse_args_no_flags() {
+        let args = Args::try_parse_from(["upgrade-helper"]).expect("no flags are required");
+        assert_eq!(args.network, None);
+        assert_eq!(args.upgrade_date, None);
+    }
+
+    #[test]
+    fn test_parse_args_config() {
+        let args = Args::try_parse_from(["upgrade-helper", "--config", "upgrade.toml"])
+            .expect("the arguments should be parsed");
+        assert_eq!(args.config, Some(PathBuf::from("upgrade.toml")));
+    }
+
+    #[test]
+    fn test_parse_args_config_conflicts() {
+        let res = Args::try_parse_from([
+            "upgrade-helper",
+            "--config",
+            "upgrade.toml",
+            "--network",
+            "mainnet",
+        ]);
+        assert!(res.is_err(), "expected an error for conflicting arguments");
+    }
+
+    #[test]
+    fn test_parse_args_template_conflicts() {
+        let res = Args::try_parse_from([
+            "upgrade-helper",
+            "--template",
+            "proposal.hbs",
+            "--template-dir",
+            "templates",
+        ]);
+        assert!(
+            res.is_err(),
+            "expected an error for both a template and a template directory"
+        );
+    }
+
+    #[test]
+    fn test_parse_args_project() {
+        let args = Args::try_parse_from([
+            "upgrade-helper",
+            "--author",
+            "Gaia Team",
+            "--repository",
+            "cosmos/gaia",
+        ])
+        .expect("the arguments should be parsed");
+        assert_eq!(args.author.as_deref(), Some("Gaia Team"));
+        assert_eq!(args.repository, Some("cosmos/gaia".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_args_watch() {
+        let args = Args::try_parse_from([
+            "upgrade-helper",
+            "--network",
+            "mainnet",
+            "watch",
+            "--height",
+            "17000000",
+            "--announced-time",
+            "2023-11-06T16:00:00Z",
+        ])
+        .expect("the arguments should be parsed");
+        assert_eq!(args.network, Some(Network::Mainnet));
+        assert!(args.check_conflicts().is_ok());
+
+        let Some(Command::Watch(watch_args)) = args.command else {
+            panic!("expected the watch command");
+        };
+        assert_eq!(watch_args.height, 17_000_000);
+        assert_eq!(
+            watch_args.announced_time,
+            "2023-11-06T16:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(watch_args.max_drift_minutes, 30);
+        assert_eq!(watch_args.interval_minutes, 10);
+    }
+
+    #[test]
+    fn test_parse_args_watch_conflicts_with_height() {
+        let args = Args::try_parse_from([
+            "upgrade-helper",
+            "--height",
+            "17000000",
+            "watch",
+            "--height",
+            "17000000",
+            "--announced-time",
+            "2023-11-06T16:00:00Z",
+        ])
+        .expect("the arguments should be parsed");
+        assert!(
+            args.check_conflicts().is_err(),
+            "expected an error for passing the height to the main command and the watch command"
+        );
+    }
+
+    #[test]
+    fn test_parse_args_watch_missing_height() {
+        let res = Args::try_parse_from([
+            "upgrade-helper",
+            "watch",
+            "--announced-time",
+            "2023-11-06T16:00:00Z",
+        ]);
+        assert!(res.is_err(), "expected an error for a missing height");
+    }
+
+    #[test]
+    fn test_parse_args_watch_invalid_interval() {
+        let res = Args::try_parse_from([
+            "upgrade-helper",
+            "watch",
+            "--height",
+            "17000000",
+            "--announced-time",
+            "2023-11-06T16:00:00Z",
+            "--interval-minutes",
+            "0",
+        ]);
+        assert!(res.is_err(), "expected an error for an empty interval");
+    }
+
+    #[test]
+    fn test_parse_args_round_to() {
+        let args = Args::try_parse_from([
+            "upgrade-helper",
+            "--round-to",
+            "1000",
+            "--max-drift-minutes",
+            "15",
+        ])
+        .expect("the arguments should be parsed");
+        assert_eq!(args.round_to, Some(1000));
+        assert_eq!(args.max_drift_minutes, Some(15));
+    }
+
+    #[test]
+    fn test_parse_args_height() {
+        let args = Args::try_parse_from([
+            "upgrade-helper",
+            "--network",
+            "mainnet",
+            "--height",
+            "17000000",
+        ])
+        .expect("the arguments should be parsed");
+        assert_eq!(args.height, Some(17_000_000));
+
+        let res = Args::try_parse_from([
+            "upgrade-helper",
+            "--height",
+            "17000000",
+            "--target-version",
+            "v15.0.0",
+        ]);
+        assert!(
+            res.is_err(),
+            "expected an error for a height and a target version"
+        );
+    }
+
+    #[test]
+    fn test_parse_args_block_source() {
+        let args = Args::try_parse_from(["upgrade-helper", "--block-source", "rpc"])
+            .expect("the arguments should be parsed");
+        assert_eq!(args.block_source, Some(BlockSource::Rpc));
+
+        let res = Args::try_parse_from(["upgrade-helper", "--block-source", "grpc"]);
+        assert!(
+            res.is_err(),
+            "expected an error for an unknown block source"
+        );
+    }
+
+    #[test]
+    fn test_parse_args_profile_conflicts() {
+        let res = Args::try_parse_from([
+            "upgrade-helper",
+            "--profile",
+            "gaia.toml",
+            "--network",
+            "mainnet",
+        ]);
+        assert!(
+            res.is_err(),
+            "expected an error for both a profile and a network"
+        );
+    }
+
+    #[test]
+    fn test_parse_args_chain() {
+        let args = Args::try_parse_from([
+            "upgrade-helper",
+            "--chain-registry",
+            "../chain-registry",
+            "--chain",
+            "evmos",
+        ])
+        .expect("the arguments should be parsed");
+        assert_eq!(
+            args.chain_registry,
+            Some(PathBuf::from("../chain-registry"))
+        );
+        assert_eq!(args.chain.as_deref(), Some("evmos"));
+
+        let res = Args::try_parse_from(["upgrade-helper", "--chain", "evmos"]);
+        assert!(res.is_err(), "expected an error without the chain registry");
+    }
+
+    #[test]
+    fn test_parse_args_invalid_version() {
+        let res = Args::try_parse_from(["upgrade-helper", "--previous-version", "v14.0"]);
+        assert!(res.is_err(), "expected an error for an invalid version");
+    }
+
+    #[test]
+    fn test_parse_args_invalid_network() {
+        let res = Args::try_parse_from(["upgrade-helper", "--network", "devnet"]);
+        assert!(res.is_err(), "expected an error for an invalid network");
+    }
+}
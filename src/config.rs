@@ -0,0 +1,382 @@
+use crate::{
+    error::Error,
+    gov::Coin,
+    network::Network,
+    profile::ChainProfile,
+    project::Project,
+    registry,
+    version::{self, Version},
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Declarative description of an upgrade, which can be committed to a repository
+/// (e.g. as `upgrades/v15.0.0-mainnet.toml`) to make the upgrade plan reviewable and reproducible.
+///
+/// ```toml
+/// network = "mainnet"
+/// previous_version = "v14.0.0"
+/// target_version = "v15.0.0"
+/// upgrade_time = "2023-11-06T16:00:00Z"
+/// voting_period_hours = 120
+/// features = ["Add the vesting precompile", "Remove the claims module"]
+/// metadata = "ipfs://CID"
+/// deposit = "2000000000000000000000aevmos"
+/// expedited = false
+/// round_to = 1000
+/// max_drift_minutes = 30
+/// template_dir = "templates"
+///
+/// [project]
+/// author = "Malte Herrmann, Evmos Core Team"
+/// ```
+///
+/// Exactly one of the `network` of a built-in profile, a custom `[profile]` table (see [`ChainProfile`])
+/// or a `chain` of the local chain registry checkout at `chain_registry` (see [`registry::load_chain`])
+/// has to be configured.
+/// Either a single `template` file or a `template_dir` containing per-network templates
+/// (see [`crate::proposal::find_template`]) can be configured.
+/// All keys of the `[project]` table are optional (see [`Project`]).
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpgradeConfig {
+    pub network: Option<Network>,
+    pub previous_version: Version,
+    pub target_version: Version,
+    pub upgrade_time: DateTime<Utc>,
+    pub voting_period_hours: Option<i64>,
+    pub features: Option<Vec<String>>,
+    pub metadata: Option<String>,
+    pub deposit: Option<Coin>,
+    /// Submits the upgrade as an expedited proposal.
+    #[serde(default)]
+    pub expedited: bool,
+    /// Granularity, to which the upgrade height is rounded, see [`crate::UpgradeHelper::round_upgrade_height`].
+    pub round_to: Option<u64>,
+    pub max_drift_minutes: Option<i64>,
+    pub template: Option<PathBuf>,
+    pub template_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub project: Project,
+    pub profile: Option<ChainProfile>,
+    pub chain_registry: Option<PathBuf>,
+    pub chain: Option<String>,
+}
+
+impl UpgradeConfig {
+    /// Checks the loaded values for consistency.
+    /// The returned error message contains the key of the offending value.
+    fn validate(&self) -> Result<(), Error> {
+        let profile = self.chain_profile()?;
+
+        if !version::is_valid_target_version(&profile.version_policy(), &self.target_version) {
+            return Err(Error::Config(format!(
+                "invalid value for key `target_version` on {}: {}",
+                profile.display_name, self.target_version
+            )));
+        }
+
+        if self.template.is_some() && self.template_dir.is_some() {
+            return Err(Error::Config(
+                "only one of the keys `template` and `template_dir` can be set".to_string(),
+            ));
+        }
+
+        if let Some(hours) = self.voting_period_hours {
+            if hours <= 0 {
+                return Err(Error::Config(format!(
+                    "invalid value for key `voting_period_hours`: {}; must be positive",
+                    hours
+                )));
+            }
+        }
+
+        if self.round_to == Some(0) {
+            return Err(Error::Config(
+                "invalid value for key `round_to`: 0; must be positive".to_string(),
+            ));
+        }
+
+        if let Some(minutes) = self.max_drift_minutes {
+            if minutes < 0 {
+                return Err(Error::Config(format!(
+                    "invalid value for key `max_drift_minutes`: {}; must not be negative",
+                    minutes
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the chain profile of the configured network.
+    /// Chains of the chain registry are loaded from the configured registry checkout.
+    /// The legacy keys of the `[project]` table are applied to the profile.
+    pub fn chain_profile(&self) -> Result<ChainProfile, Error> {
+        let mut profile = match (self.network, &self.profile, &self.chain) {
+            (Some(network), None, None) => ChainProfile::builtin(network),
+            (None, Some(profile), None) => profile.clone(),
+            (None, None, Some(chain)) => match &self.chain_registry {
+                Some(registry_dir) => registry::load_chain(registry_dir, chain)?,
+                None => {
+                    return Err(Error::Config(
+                        "missing key `chain_registry` to load the chain from".to_string(),
+                    ))
+                }
+            },
+            _ => return Err(Error::Config(
+                "exactly one of the keys `network`, `chain` and the table `profile` must be set"
+                    .to_string(),
+            )),
+        };
+        self.project.apply_legacy_keys(&mut profile);
+        profile.validate()?;
+
+        Ok(profile)
+    }
+}
+
+/// Parses the upgrade configuration from the given TOML contents.
+pub fn parse_config(contents: &str) -> Result<UpgradeConfig, Error> {
+    let config: UpgradeConfig =
+        toml::from_str(contents).map_err(|e| Error::Config(e.to_string()))?;
+    config.validate()?;
+
+    Ok(config)
+}
+
+/// Loads the upgrade configuration from the TOML file at the given path.
+pub fn load_config(path: &Path) -> Result<UpgradeConfig, Error> {
+    let contents = std::fs::read_to_string(path)?;
+
+    parse_config(contents.as_str()).map_err(|e| match e {
+        Error::Config(msg) => Error::Config(format!("{}: {}", path.display(), msg)),
+        _ => e,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const CONFIG: &str = r#"
+        network = "mainnet"
+        previous_version = "v14.0.0"
+        target_version = "v15.0.0"
+        upgrade_time = "2023-11-06T16:00:00Z"
+        voting_period_hours = 96
+        features = ["Add the vesting precompile", "Remove the claims module"]
+        metadata = "ipfs://CID"
+        deposit = "2000aevmos"
+        expedited = true
+
+        [project]
+        author = "Evmos Core Team"
+        upgrade_guide = "https://docs.example.com/upgrades"
+    "#;
+
+    #[test]
+    fn test_parse_config_pass() {
+        let config = parse_config(CONFIG).expect("the config should be parsed");
+        assert_eq!(config.network, Some(Network::Mainnet));
+        assert_eq!(
+            config.chain_profile().unwrap(),
+            ChainProfile::builtin(Network::Mainnet)
+        );
+        assert_eq!(config.previous_version.to_string(), "v14.0.0");
+        assert_eq!(config.target_version.to_string(), "v15.0.0");
+        assert_eq!(
+            config.upgrade_time,
+            Utc.with_ymd_and_hms(2023, 11, 6, 16, 0, 0).unwrap()
+        );
+        assert_eq!(config.voting_period_hours, Some(96));
+        assert_eq!(config.project.author, "Evmos Core Team");
+        assert_eq!(
+            config.project.upgrade_guide,
+            "https://docs.example.com/upgrades"
+        );
+        assert_eq!(
+            config.project.e2e_guide,
+            Project::default().e2e_guide,
+            "expected the default for a missing project key"
+        );
+        assert_eq!(config.features.unwrap().len(), 2);
+        assert_eq!(config.metadata.as_deref(), Some("ipfs://CID"));
+        assert_eq!(config.deposit, Some("2000aevmos".parse().unwrap()));
+        assert!(config.expedited);
+    }
+
+    #[test]
+    fn test_parse_config_optional_keys() {
+        let config = parse_config(
+            r#"
+            network = "testnet"
+            previous_version = "v14.0.0"
+            target_version = "v15.0.0-rc1"
+            upgrade_time = "2023-11-06T16:00:00Z"
+            "#,
+        )
+        .expect("the config should be parsed");
+        assert_eq!(config.voting_period_hours, None);
+        assert_eq!(config.project, Project::default());
+        assert!(config.features.is_none());
+        assert!(!config.expedited);
+    }
+
+    #[test]
+    fn test_parse_config_custom_profile() {
+        let config = parse_config(
+            r#"
+            previous_version = "v14.0.0"
+            target_version = "v14.10.0"
+            upgrade_time = "2023-11-06T16:00:00Z"
+
+            [profile]
+            name = "gaia-mainnet"
+            network = "mainnet"
+            chain_name = "Cosmos Hub"
+            display_name = "Cosmos Hub Mainnet"
+            chain_id = "cosmoshub-4"
+            rest_endpoints = ["https://rest.cosmos.directory/cosmoshub"]
+            rpc_endpoint = "https://rpc.cosmos.directory/cosmoshub"
+            explorer = "https://www.mintscan.io/cosmos/blocks"
+            repository = "cosmos/gaia"
+            daemon = "gaiad"
+            gov_authority = "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn"
+            gov = { voting_period_hours = 336, deposit = "250000000uatom" }
+            "#,
+        )
+        .expect("the config should be parsed");
+        assert_eq!(config.network, None);
+        assert_eq!(config.chain_profile().unwrap().chain_id, "cosmoshub-4");
+    }
+
+    #[test]
+    fn test_parse_config_legacy_project_keys() {
+        let config = parse_config(&format!(
+            "{}\nrepository = \"cosmos/gaia\"\nmainnet_explorer = \"https://explorer.com/blocks\"",
+            CONFIG
+        ))
+        .expect("the legacy project keys should be accepted");
+        let profile = config.chain_profile().unwrap();
+        assert_eq!(profile.repository.to_string(), "cosmos/gaia");
+        assert_eq!(profile.explorer, "https://explorer.com/blocks");
+
+        assert!(
+            parse_config(&format!("{}\nmainnet_explorer = \"explorer\"", CONFIG)).is_err(),
+            "expected an error for an invalid legacy explorer"
+        );
+    }
+
+    #[test]
+    fn test_parse_config_chain_without_registry() {
+        let err = parse_config(&CONFIG.replace("network = \"mainnet\"", "chain = \"evmos\""))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("chain_registry"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_config_missing_network() {
+        let err = parse_config(&CONFIG.replace("network = \"mainnet\"", "")).unwrap_err();
+        assert!(
+            err.to_string().contains("network"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_config_round_to() {
+        let config = parse_config(&format!(
+            "round_to = 1000
+max_drift_minutes = 15
+{}",
+            CONFIG
+        ))
+        .expect("the config should be parsed");
+        assert_eq!(config.round_to, Some(1000));
+        assert_eq!(config.max_drift_minutes, Some(15));
+
+        let err = parse_config(&format!(
+            "round_to = 0
+{}",
+            CONFIG
+        ))
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("round_to"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_config_template_conflict() {
+        let err = parse_config(&format!(
+            "template = \"proposal.hbs\"\ntemplate_dir = \"templates\"\n{}",
+            CONFIG
+        ))
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("template_dir"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_config_invalid_network() {
+        let err = parse_config(&CONFIG.replace("\"mainnet\"", "\"devnet\"")).unwrap_err();
+        assert!(
+            err.to_string().contains("network"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_config_unknown_key() {
+        let err = parse_config(&format!("voting_time = 1\n{}", CONFIG)).unwrap_err();
+        assert!(
+            err.to_string().contains("voting_time"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_config_invalid_deposit() {
+        let err = parse_config(&CONFIG.replace("\"2000aevmos\"", "\"2000\"")).unwrap_err();
+        assert!(
+            err.to_string().contains("deposit"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_config_invalid_previous_version() {
+        let err = parse_config(&CONFIG.replace("\"v14.0.0\"", "\"v14.0\"")).unwrap_err();
+        assert!(
+            err.to_string().contains("previous_version"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_config_invalid_target_version() {
+        let err = parse_config(&CONFIG.replace("\"v15.0.0\"", "\"v15.0.0-rc1\"")).unwrap_err();
+        assert!(
+            err.to_string().contains("target_version"),
+            "unexpected error: {}",
+            err
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use thiserror::Error;
+
+/// Errors that can occur when preparing an upgrade.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("network request failed: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("invalid URL: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("failed to parse {0}")]
+    Parse(String),
+    #[error("invalid upgrade configuration:\n  - {}", .0.join("\n  - "))]
+    Validation(Vec<String>),
+    #[error("invalid config: {0}")]
+    Config(String),
+    #[error("failed to prepare template: {0}")]
+    Template(Box<handlebars::TemplateError>),
+    #[error("template uses undefined variables: {}", .0.join(", "))]
+    MissingVariables(Vec<String>),
+    #[error("failed to render template: {0}")]
+    Render(#[from] handlebars::RenderError),
+    #[error("failed to read input: {0}")]
+    Input(#[from] inquire::InquireError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to estimate the upgrade height: {0}")]
+    Estimation(String),
+    #[error("upgrade time drifted: {0}")]
+    Drift(String),
+    #[error("invalid release: {0}")]
+    Release(String),
+    #[error("GitHub request failed: {0}")]
+    GitHub(Box<octocrab::Error>),
+}
+
+impl From<handlebars::TemplateError> for Error {
+    fn from(e: handlebars::TemplateError) -> Self {
+        Error::Template(Box::new(e))
+    }
+}
+
+impl From<octocrab::Error> for Error {
+    fn from(e: octocrab::Error) -> Self {
+        Error::GitHub(Box::new(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_error_lists_all_problems() {
+        let err = Error::Validation(vec![
+            "invalid previous version: v14".to_string(),
+            "invalid upgrade time".to_string(),
+        ]);
+        assert_eq!(
+            err.to_string(),
+            "invalid upgrade configuration:\n  - invalid previous version: v14\n  - invalid upgrade time",
+        );
+    }
+}
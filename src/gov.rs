@@ -0,0 +1,407 @@
+use crate::{
+    error::Error,
+    profile::ChainProfile,
+    rest,
+    upgrade::{parse_plan, PendingPlan},
+};
+use chrono::Duration;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{fmt, str::FromStr};
+use url::form_urlencoded;
+
+/// Represents an amount of tokens of a given denomination, e.g. `1000aevmos`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(try_from = "String")]
+pub struct Coin {
+    pub amount: u128,
+    pub denom: String,
+}
+
+impl fmt::Display for Coin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
+}
+
+impl FromStr for Coin {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let denom_start = s
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or(format!("invalid coin: {}; missing denomination", s))?;
+        let (amount, denom) = s.split_at(denom_start);
+        let amount = amount
+            .parse::<u128>()
+            .map_err(|e| format!("invalid coin amount: {}; {}", s, e))?;
+
+        Ok(Coin {
+            amount,
+            denom: denom.to_string(),
+        })
+    }
+}
+
+impl TryFrom<String> for Coin {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Represents the deposit parameters of the governance module.
+#[derive(Debug, PartialEq)]
+pub struct DepositParams {
+    pub min_deposit: Vec<Coin>,
+    /// The minimum deposit for expedited proposals, which is only available on chains using Cosmos SDK v0.50+.
+    pub expedited_min_deposit: Vec<Coin>,
+}
+
+/// Represents the voting parameters of the governance module.
+#[derive(Debug, PartialEq)]
+pub struct VotingParams {
+    pub voting_period: Duration,
+    /// The voting period for expedited proposals, which is only available on chains using Cosmos SDK v0.50+.
+    pub expedited_voting_period: Option<Duration>,
+}
+
+/// Queries the voting parameters of the governance module from the given chain.
+pub async fn get_voting_params(profile: &ChainProfile) -> Result<VotingParams, Error> {
+    let body = rest::get(profile, "cosmos/gov/v1/params/voting").await?;
+
+    process_voting_params_body(body.as_str())
+}
+
+/// Processes the response body of the voting parameters query.
+/// Newer SDK versions return the values in `params` while older versions only
+/// contain the deprecated `voting_params` field.
+fn process_voting_params_body(body: &str) -> Result<VotingParams, Error> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| Error::Parse(format!("voting params response body: {}", e)))?;
+
+    let voting_period = value["params"]["voting_period"]
+        .as_str()
+        .or(value["voting_params"]["voting_period"].as_str())
+        .ok_or(Error::Parse("voting period from response body".to_string()))?;
+
+    let expedited_voting_period = match value["params"]["expedited_voting_period"].as_str() {
+        Some(period) => Some(parse_duration(period)?),
+        None => None,
+    };
+
+    Ok(VotingParams {
+        voting_period: parse_duration(voting_period)?,
+        expedited_voting_period,
+    })
+}
+
+/// Queries the deposit parameters of the governance module from the given chain.
+pub async fn get_deposit_params(profile: &ChainProfile) -> Result<DepositParams, Error> {
+    let body = rest::get(profile, "cosmos/gov/v1/params/deposit").await?;
+
+    process_deposit_params_body(body.as_str())
+}
+
+/// Processes the response body of the deposit parameters query.
+fn process_deposit_params_body(body: &str) -> Result<DepositParams, Error> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| Error::Parse(format!("deposit params response body: {}", e)))?;
+
+    let min_deposit = match value["params"]["min_deposit"] {
+        Value::Array(_) => &value["params"]["min_deposit"],
+        _ => &value["deposit_params"]["min_deposit"],
+    };
+    if !min_deposit.is_array() {
+        return Err(Error::Parse(
+            "minimum deposit from response body".to_string(),
+        ));
+    }
+
+    Ok(DepositParams {
+        min_deposit: parse_coins(min_deposit)?,
+        expedited_min_deposit: parse_coins(&value["params"]["expedited_min_deposit"])?,
+    })
+}
+
+/// Parses the coins from the given JSON array, e.g. `[{"denom": "aevmos", "amount": "1000"}]`.
+fn parse_coins(value: &Value) -> Result<Vec<Coin>, Error> {
+    let coins = match value.as_array() {
+        Some(coins) => coins,
+        None => return Ok(Vec::new()),
+    };
+
+    coins
+        .iter()
+        .map(|coin| {
+            let amount = coin["amount"]
+                .as_str()
+                .and_then(|amount| amount.parse::<u128>().ok())
+                .ok_or(Error::Parse(format!("coin amount: {}", coin)))?;
+            let denom = coin["denom"]
+                .as_str()
+                .ok_or(Error::Parse(format!("coin denomination: {}", coin)))?;
+
+            Ok(Coin {
+                amount,
+                denom: denom.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Checks if the given deposit is below the minimum deposit.
+/// A deposit in a denomination that is not part of the minimum deposit is considered insufficient.
+pub fn is_below_min_deposit(deposit: &Coin, min_deposit: &[Coin]) -> bool {
+    if min_deposit.is_empty() {
+        return false;
+    }
+
+    match min_deposit.iter().find(|coin| coin.denom == deposit.denom) {
+        Some(min) => deposit.amount < min.amount,
+        None => true,
+    }
+}
+
+/// Queries the software upgrade plans of all proposals, which are currently in their voting period.
+/// All pages of the proposals query are requested.
+pub async fn get_proposed_plans(profile: &ChainProfile) -> Result<Vec<PendingPlan>, Error> {
+    let mut plans: Vec<PendingPlan> = Vec::new();
+    let mut next_key: Option<String> = None;
+
+    loop {
+        let mut endpoint =
+            "cosmos/gov/v1/proposals?proposal_status=PROPOSAL_STATUS_VOTING_PERIOD".to_string();
+        if let Some(key) = &next_key {
+            let key: String = form_urlencoded::byte_serialize(key.as_bytes()).collect();
+            endpoint.push_str(format!("&pagination.key={}", key).as_str());
+        }
+
+        let body = rest::get(profile, endpoint.as_str()).await?;
+        let (page_plans, page_next_key) = process_proposals_body(body.as_str())?;
+        plans.extend(page_plans);
+
+        match page_next_key {
+            Some(key) => next_key = Some(key),
+            None => return Ok(plans),
+        }
+    }
+}
+
+/// Processes the response body of the proposals query and returns the contained upgrade plans
+/// together with the key of the next page, if there is one.
+/// Both `MsgSoftwareUpgrade` messages and legacy `SoftwareUpgradeProposal` contents are considered.
+fn process_proposals_body(body: &str) -> Result<(Vec<PendingPlan>, Option<String>), Error> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| Error::Parse(format!("proposals response body: {}", e)))?;
+    let proposals = value["proposals"]
+        .as_array()
+        .ok_or(Error::Parse("proposals from response body".to_string()))?;
+
+    let mut plans: Vec<PendingPlan> = Vec::new();
+    for proposal in proposals {
+        let proposal_id = proposal["id"]
+            .as_str()
+            .and_then(|id| id.parse::<u64>().ok())
+            .ok_or(Error::Parse(format!("proposal id: {}", proposal["id"])))?;
+
+        for message in proposal["messages"].as_array().unwrap_or(&Vec::new()) {
+            let plan = match message["@type"].as_str() {
+                Some("/cosmos.upgrade.v1beta1.MsgSoftwareUpgrade") => &message["plan"],
+                Some("/cosmos.gov.v1.MsgExecLegacyContent")
+                    if message["content"]["@type"]
+                        == "/cosmos.upgrade.v1beta1.SoftwareUpgradeProposal" =>
+                {
+                    &message["content"]["plan"]
+                }
+                _ => continue,
+            };
+
+            plans.push(PendingPlan::Proposed {
+                proposal_id,
+                plan: parse_plan(plan)?,
+            });
+        }
+    }
+
+    let next_key = value["pagination"]["next_key"]
+        .as_str()
+        .filter(|key| !key.is_empty())
+        .map(String::from);
+
+    Ok((plans, next_key))
+}
+
+/// Parses a protobuf JSON duration like `432000s` or `0.5s`.
+pub(crate) fn parse_duration(duration: &str) -> Result<Duration, Error> {
+    let seconds = duration
+        .strip_suffix('s')
+        .and_then(|seconds| seconds.parse::<f64>().ok())
+        .ok_or(Error::Parse(format!("duration {}", duration)))?;
+
+    Ok(Duration::milliseconds((seconds * 1000.0).round() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+
+    #[tokio::test]
+    async fn test_get_voting_params_mainnet() {
+        let params = get_voting_params(&ChainProfile::builtin(Network::Mainnet))
+            .await
+            .expect("the voting params should be queried");
+        assert!(params.voting_period > Duration::zero());
+    }
+
+    #[test]
+    fn test_process_voting_params_body_legacy() {
+        let body = r#"{"voting_params":{"voting_period":"432000s"},"deposit_params":null,"tally_params":null}"#;
+        let params = process_voting_params_body(body).unwrap();
+        assert_eq!(
+            params,
+            VotingParams {
+                voting_period: Duration::hours(120),
+                expedited_voting_period: None,
+            },
+        );
+    }
+
+    #[test]
+    fn test_process_voting_params_body_expedited() {
+        let body = r#"{"voting_params":{"voting_period":"43200s"},"params":{"voting_period":"43200s","expedited_voting_period":"3600s"}}"#;
+        let params = process_voting_params_body(body).unwrap();
+        assert_eq!(
+            params,
+            VotingParams {
+                voting_period: Duration::hours(12),
+                expedited_voting_period: Some(Duration::hours(1)),
+            },
+        );
+    }
+
+    #[test]
+    fn test_process_voting_params_body_fail() {
+        let res = process_voting_params_body(r#"{"code":12,"message":"Not Implemented"}"#);
+        assert!(
+            res.is_err(),
+            "expected an error for a missing voting period"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_deposit_params_mainnet() {
+        let params = get_deposit_params(&ChainProfile::builtin(Network::Mainnet))
+            .await
+            .expect("the deposit params should be queried");
+        assert!(!params.min_deposit.is_empty());
+    }
+
+    #[test]
+    fn test_process_deposit_params_body_legacy() {
+        let body = r#"{"voting_params":null,"deposit_params":{"min_deposit":[{"denom":"aevmos","amount":"2000000000000000000000"}],"max_deposit_period":"1209600s"},"tally_params":null}"#;
+        let params = process_deposit_params_body(body).unwrap();
+        assert_eq!(
+            params.min_deposit,
+            vec![Coin {
+                amount: 2_000_000_000_000_000_000_000,
+                denom: "aevmos".to_string(),
+            }],
+        );
+        assert!(params.expedited_min_deposit.is_empty());
+    }
+
+    #[test]
+    fn test_process_deposit_params_body_expedited() {
+        let body = r#"{"params":{"min_deposit":[{"denom":"atevmos","amount":"10"}],"expedited_min_deposit":[{"denom":"atevmos","amount":"50"}]}}"#;
+        let params = process_deposit_params_body(body).unwrap();
+        assert_eq!(params.min_deposit, vec!["10atevmos".parse().unwrap()]);
+        assert_eq!(
+            params.expedited_min_deposit,
+            vec!["50atevmos".parse().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_process_deposit_params_body_fail() {
+        let res = process_deposit_params_body(r#"{"code":12,"message":"Not Implemented"}"#);
+        assert!(
+            res.is_err(),
+            "expected an error for a missing minimum deposit"
+        );
+    }
+
+    #[test]
+    fn test_coin_from_str() {
+        let coin: Coin = "1000aevmos".parse().unwrap();
+        assert_eq!(coin.amount, 1000);
+        assert_eq!(coin.denom, "aevmos");
+        assert_eq!(coin.to_string(), "1000aevmos");
+        assert!("aevmos".parse::<Coin>().is_err());
+        assert!("1000".parse::<Coin>().is_err());
+    }
+
+    #[test]
+    fn test_is_below_min_deposit() {
+        let min_deposit = vec!["1000aevmos".parse().unwrap()];
+        assert!(!is_below_min_deposit(
+            &"1000aevmos".parse().unwrap(),
+            &min_deposit
+        ));
+        assert!(is_below_min_deposit(
+            &"999aevmos".parse().unwrap(),
+            &min_deposit
+        ));
+        assert!(is_below_min_deposit(
+            &"1000atevmos".parse().unwrap(),
+            &min_deposit
+        ));
+        assert!(!is_below_min_deposit(&"1aevmos".parse().unwrap(), &[]));
+    }
+
+    #[test]
+    fn test_process_proposals_body() {
+        let body = r#"{"proposals":[
+            {"id":"1","messages":[{"@type":"/cosmos.upgrade.v1beta1.MsgSoftwareUpgrade","authority":"evmos10d07y265gmmuvt4z0w9aw880jnsr700jcrztvm","plan":{"name":"v15.0.0","height":"16800000","info":""}}]},
+            {"id":"2","messages":[{"@type":"/cosmos.gov.v1.MsgExecLegacyContent","content":{"@type":"/cosmos.upgrade.v1beta1.SoftwareUpgradeProposal","plan":{"name":"v14.0.0","height":"16000000","info":""}}}]},
+            {"id":"3","messages":[{"@type":"/cosmos.bank.v1beta1.MsgSend"}]}
+        ],"pagination":{"next_key":null,"total":"3"}}"#;
+
+        let (plans, next_key) = process_proposals_body(body).unwrap();
+        assert_eq!(plans.len(), 2, "expected only the upgrade proposals");
+        assert_eq!(next_key, None, "expected no further page");
+        assert_eq!(
+            plans[1],
+            PendingPlan::Proposed {
+                proposal_id: 2,
+                plan: crate::upgrade::Plan {
+                    name: "v14.0.0".to_string(),
+                    height: 16_000_000,
+                },
+            },
+        );
+    }
+
+    #[test]
+    fn test_process_proposals_body_next_key() {
+        let body = r#"{"proposals":[],"pagination":{"next_key":"AAAAAAAAAAM=","total":"0"}}"#;
+        let (plans, next_key) = process_proposals_body(body).unwrap();
+        assert!(plans.is_empty());
+        assert_eq!(next_key.as_deref(), Some("AAAAAAAAAAM="));
+    }
+
+    #[test]
+    fn test_process_proposals_body_fail() {
+        let res = process_proposals_body(r#"{"code":12,"message":"Not Implemented"}"#);
+        assert!(res.is_err(), "expected an error for missing proposals");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("60s").unwrap(), Duration::minutes(1));
+        assert_eq!(parse_duration("0.5s").unwrap(), Duration::milliseconds(500));
+        assert!(parse_duration("60").is_err());
+    }
+}
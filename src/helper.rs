@@ -1,111 +1,673 @@
-use crate::{block::get_estimated_height, inputs, network::Network, proposal, release, version};
+use crate::{
+    block::{self, get_estimated_height, ConfidenceInterval},
+    changelog,
+    error::Error,
+    gov::{self, Coin, DepositParams, VotingParams},
+    inputs,
+    profile::ChainProfile,
+    project::{Project, Repository},
+    proposal, release,
+    upgrade::{self, PendingPlan},
+    version::{self, UpgradeType, Version},
+};
 use chrono::{DateTime, Duration, Utc};
-use std::process;
+use std::path::PathBuf;
 
+/// Problem reported if neither the minimum deposit could be queried nor a default deposit is configured.
+const NO_DEPOSIT: &str = "no deposit set: the minimum deposit could not be queried and the chain profile has no default deposit; pass one with --deposit";
+
+/// Contains all information needed to prepare an upgrade proposal.
 pub struct UpgradeHelper {
-    pub network: Network,
-    pub previous_version: String,
-    pub target_version: String,
+    pub profile: ChainProfile,
+    pub previous_version: Version,
+    pub target_version: Version,
     pub proposal_name: String,
     pub upgrade_time: DateTime<Utc>,
+    pub upgrade_type: UpgradeType,
     pub voting_period: Duration,
+    pub expedited: bool,
     pub upgrade_height: u64,
+    /// Description of how the upgrade height was estimated, see [`crate::block::BlockTimeEstimate::method`].
+    pub estimation_method: String,
+    /// Interval, in which the upgrade height is expected to be reached, see [`crate::block::HeightEstimate`].
+    pub upgrade_time_interval: Option<ConfidenceInterval>,
+    /// Estimated block time, that is used to convert between block heights and times.
+    pub seconds_per_block: f64,
+    pub output_dir: PathBuf,
+    pub project: Project,
+    pub features: Vec<String>,
+    pub metadata: String,
+    /// Path to a custom proposal template; the embedded default template is used if not set.
+    pub template: Option<PathBuf>,
+    /// Deposit to submit the proposal with, which is not set if it can neither be queried nor derived from the chain profile.
+    pub deposit: Option<Coin>,
+    pub min_deposit: Vec<Coin>,
+    pub allow_conflicts: bool,
 }
 
 impl UpgradeHelper {
     /// Creates a new instance of the upgrade helper.
+    /// An expedited proposal is only prepared if requested and supported by the network.
     pub async fn new(
-        network: Network,
-        previous_version: &str,
-        target_version: &str,
+        profile: ChainProfile,
+        previous_version: Version,
+        target_version: Version,
         upgrade_time: DateTime<Utc>,
-    ) -> UpgradeHelper {
-        let proposal_name = format!("Evmos {} {} Upgrade", network, target_version);
-        let voting_period = get_voting_period(network);
-        let upgrade_height = get_estimated_height(network, upgrade_time).await;
-        println!("Estimated upgrade height: {}", upgrade_height);
+        expedited: bool,
+        project: Project,
+        output_dir: PathBuf,
+    ) -> Result<UpgradeHelper, Error> {
+        let proposal_name = format!("{} {} Upgrade", profile.display_name, target_version);
+        let upgrade_type = UpgradeType::from_versions(&previous_version, &target_version);
+        let voting_params = get_voting_params(&profile).await;
+        let deposit_params = get_deposit_params(&profile).await;
 
-        UpgradeHelper {
-            network,
-            previous_version: previous_version.to_string(),
-            target_version: target_version.to_string(),
+        let voting_period = get_voting_period(&profile, &voting_params, expedited)?;
+        let min_deposit = match expedited {
+            true => deposit_params.expedited_min_deposit,
+            false => deposit_params.min_deposit,
+        };
+        let estimate = get_estimated_height(&profile, upgrade_time).await?;
+        let upgrade_height = estimate.height;
+        let estimation_method = estimate.block_time.method();
+        println!(
+            "Estimated upgrade height: {} (based on {})",
+            upgrade_height, estimation_method
+        );
+        if let Some(interval) = &estimate.time_interval {
+            println!(
+                "Expected to be reached {}",
+                proposal::get_time_interval_string(interval)
+            );
+        }
+        let features = get_features(&profile.repository, &previous_version, &target_version).await;
+        let deposit = min_deposit.first().cloned().or(profile.gov.deposit.clone());
+
+        Ok(UpgradeHelper {
+            profile,
+            previous_version,
+            target_version,
             proposal_name,
+            upgrade_type,
             voting_period,
+            expedited,
             upgrade_time,
             upgrade_height,
+            estimation_method,
+            upgrade_time_interval: estimate.time_interval,
+            seconds_per_block: estimate.block_time.seconds_per_block,
+            output_dir,
+            project,
+            features,
+            metadata: String::new(),
+            deposit,
+            min_deposit,
+            allow_conflicts: false,
+            template: None,
+        })
+    }
+
+    /// Rounds the upgrade height to the nearest multiple of the given granularity,
+    /// e.g. 1,000, and shifts the estimated upgrade time accordingly.
+    /// The rounding is refused if the upgrade time would be shifted by more than the given maximum drift.
+    pub fn round_upgrade_height(
+        &mut self,
+        granularity: u64,
+        max_drift: Duration,
+    ) -> Result<(), Error> {
+        if granularity == 0 {
+            return Err(Error::Validation(vec![
+                "invalid rounding granularity: must be positive".to_string(),
+            ]));
+        }
+
+        let rounded_height = block::round_height(self.upgrade_height, granularity);
+        let shifted_blocks = rounded_height as f64 - self.upgrade_height as f64;
+        let drift = Duration::milliseconds(
+            (shifted_blocks * self.seconds_per_block * 1000.0).round() as i64,
+        );
+        if drift.num_milliseconds().abs() > max_drift.num_milliseconds() {
+            return Err(Error::Validation(vec![format!(
+                "rounding the upgrade height {} to {} shifts the upgrade time by {} minutes, which exceeds the maximum drift of {} minutes",
+                self.upgrade_height,
+                rounded_height,
+                drift.num_minutes(),
+                max_drift.num_minutes()
+            )]));
+        }
+
+        println!(
+            "Rounded the upgrade height from {} to {}, which shifts the upgrade time by {} seconds",
+            self.upgrade_height,
+            rounded_height,
+            drift.num_seconds()
+        );
+        self.upgrade_height = rounded_height;
+        self.upgrade_time += drift;
+        if let Some(interval) = self.upgrade_time_interval.as_mut() {
+            interval.earliest += drift;
+            interval.latest += drift;
         }
+
+        Ok(())
     }
 
     /// Validates the upgrade helper.
-    pub fn validate(&self) {
-        // Check if the target version is valid
-        let valid_version =
-            version::is_valid_target_version(self.network, self.target_version.as_str());
-        if !valid_version {
-            println!(
-                "Invalid target version for {}: {}",
-                self.network, self.target_version
-            );
-            process::exit(1);
+    /// All found problems are collected and returned at once.
+    pub async fn validate(&self) -> Result<(), Error> {
+        let mut problems = self.check();
+        problems.extend(self.check_network().await);
+
+        if !problems.is_empty() {
+            return Err(Error::Validation(problems));
+        }
+
+        if let Some(warning) = self.get_deposit_warning() {
+            println!("WARNING: {}", warning);
         }
 
-        // Check if the previous version is valid
-        let valid_version = version::is_valid_version(self.previous_version.as_str());
+        println!("Upgrade configuration is valid");
+        Ok(())
+    }
+
+    /// Checks the upgrade configuration without querying the network and returns the found problems.
+    fn check(&self) -> Vec<String> {
+        let mut problems: Vec<String> = Vec::new();
+
+        // Check if the target version is valid
+        let version_policy = self.profile.version_policy();
+        let valid_version = version::is_valid_target_version(&version_policy, &self.target_version);
         if !valid_version {
-            println!("Invalid previous version: {}", self.previous_version);
-            process::exit(1);
+            problems.push(format!(
+                "invalid target version for {}: {}",
+                self.profile.display_name, self.target_version
+            ));
         }
 
         // Check if the upgrade time is valid
         let valid_time = inputs::is_valid_upgrade_time(self.upgrade_time);
         if !valid_time {
-            println!("Invalid upgrade time: {}", self.upgrade_time);
-            process::exit(1);
+            problems.push(format!("invalid upgrade time: {}", self.upgrade_time));
+        }
+
+        // Check if the upgrade path is valid
+        problems.extend(version::check_upgrade_path(
+            &self.previous_version,
+            &self.target_version,
+        ));
+
+        // Check if a deposit is set to submit the proposal with
+        if self.deposit.is_none() {
+            problems.push(NO_DEPOSIT.to_string());
         }
 
-        println!("Upgrade configuration is valid")
+        problems
     }
 
-    /// Runs the main logic of the upgrade helper.
-    pub async fn run(&self) {
-        // Check if release was already created
-        let release_exists = release::check_release_exists(self.target_version.as_str());
-        println!("Release exists: {}", release_exists.await);
+    /// Checks the upgrade configuration against the state of the network and returns the found problems.
+    /// Failing queries are only reported as warnings.
+    async fn check_network(&self) -> Vec<String> {
+        let mut problems: Vec<String> = Vec::new();
 
-        // Prepare proposal
-        let proposal: String;
-        let proposal_res = proposal::prepare_proposal(&self);
-        match proposal_res {
-            Ok(contents) => {
-                proposal = contents;
+        // Check if a release candidate was tested on testnet before upgrading mainnet
+        if self
+            .profile
+            .version_policy()
+            .require_tested_release_candidate
+        {
+            match release::get_release_tags(&self.profile.repository).await {
+                Ok(tags) if !version::has_release_candidate(&self.target_version, &tags) => {
+                    problems.push(format!(
+                        "no release candidate for {} was released for testing on testnet",
+                        self.target_version
+                    ))
+                }
+                Ok(_) => {}
+                Err(e) => println!("WARNING: failed to query the release candidates: {}", e),
             }
-            Err(e) => {
-                println!("Error preparing proposal: {}", e);
-                process::exit(1);
+        }
+
+        // Check if the previous version is the one running on the network
+        problems.extend(check_running_version(&self.profile, &self.previous_version).await);
+
+        // Check if there are other pending upgrades
+        let conflicts = upgrade::find_conflicting_plans(
+            self.upgrade_height,
+            &get_pending_plans(&self.profile).await,
+        );
+        if self.allow_conflicts {
+            for conflict in conflicts {
+                println!("WARNING: {}", conflict);
             }
+        } else {
+            problems.extend(conflicts);
+        }
+
+        problems
+    }
+
+    /// Returns a warning if the deposit is below the minimum deposit or cannot be checked against it.
+    /// A deposit below the minimum is valid, but the proposal will not enter the voting period.
+    fn get_deposit_warning(&self) -> Option<String> {
+        let deposit = self.deposit.as_ref()?;
+
+        if self.min_deposit.is_empty() {
+            return Some(format!(
+                "deposit of {} could not be verified, as the minimum deposit of {} is unknown",
+                deposit, self.profile.display_name
+            ));
         }
 
+        match gov::is_below_min_deposit(deposit, &self.min_deposit) {
+            true => Some(format!(
+                "deposit of {} is below the minimum deposit of {}",
+                deposit,
+                get_coins_string(&self.min_deposit)
+            )),
+            false => None,
+        }
+    }
+
+    /// Runs the main logic of the upgrade helper.
+    pub async fn run(&self) -> Result<(), Error> {
+        let deposit = self
+            .deposit
+            .as_ref()
+            .ok_or(Error::Validation(vec![NO_DEPOSIT.to_string()]))?;
+
+        // Get the binaries for the upgrade plan if the release was already created
+        let upgrade_info = match release::get_release(
+            &self.profile.repository,
+            self.target_version.to_string().as_str(),
+        )
+        .await
+        {
+            Ok(release) => release::get_upgrade_info(&release).await?.to_string(),
+            Err(e) if release::is_not_found(&e) => {
+                println!(
+                    "Release {} not found, leaving the upgrade plan info empty",
+                    self.target_version
+                );
+                String::new()
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // Prepare proposal
+        let proposal = proposal::prepare_proposal(self)?;
+
         // Write proposal to file
-        let write_res = proposal::write_proposal_to_file(
+        let proposal_path = proposal::write_proposal_to_file(
             proposal.as_str(),
-            self.network,
-            self.target_version.as_str(),
+            self.output_dir.as_path(),
+            &self.profile.name,
+            &self.target_version,
+        )?;
+        println!("Wrote proposal description to {}", proposal_path.display());
+
+        // Write the proposal JSON to submit on-chain
+        let proposal_json =
+            proposal::prepare_proposal_json(self, proposal.as_str(), upgrade_info.as_str());
+        let json_path = proposal::write_proposal_json_to_file(
+            &proposal_json,
+            self.output_dir.as_path(),
+            &self.profile.name,
+            &self.target_version,
+        )?;
+        println!("Wrote proposal JSON to {}", json_path.display());
+        println!(
+            "Submit the proposal with a deposit of {} using:\n\n  {}\n",
+            deposit,
+            proposal::get_submit_command(&self.profile.daemon, json_path.as_path())
         );
-        match write_res {
-            Ok(_) => {}
-            Err(e) => {
-                println!("Error writing proposal to file: {}", e);
-                process::exit(1);
+
+        Ok(())
+    }
+}
+
+/// Returns the voting parameters of the given network.
+/// If the query fails, the default voting period of the chain profile is used.
+pub async fn get_voting_params(profile: &ChainProfile) -> VotingParams {
+    match gov::get_voting_params(profile).await {
+        Ok(params) => {
+            println!(
+                "Voting period on {}: {} hours",
+                profile.display_name,
+                params.voting_period.num_hours()
+            );
+            if let Some(expedited) = params.expedited_voting_period {
+                println!("Expedited voting period: {} hours", expedited.num_hours());
             }
+            params
         }
+        Err(e) => {
+            let voting_period = Duration::hours(profile.gov.voting_period_hours);
+            println!(
+                "WARNING: failed to query the voting period, using the default of {} hours: {}",
+                voting_period.num_hours(),
+                e
+            );
+            VotingParams {
+                voting_period,
+                expedited_voting_period: None,
+            }
+        }
+    }
+}
+
+/// Returns the voting period of an expedited or a regular proposal from the given voting parameters.
+/// Returns an error if an expedited proposal is requested, but not supported by the network.
+pub fn get_voting_period(
+    profile: &ChainProfile,
+    voting_params: &VotingParams,
+    expedited: bool,
+) -> Result<Duration, Error> {
+    match (expedited, voting_params.expedited_voting_period) {
+        (true, Some(period)) => Ok(period),
+        (true, None) => Err(Error::Validation(vec![format!(
+            "no expedited voting period found for {}; expedited proposals require Cosmos SDK v0.50+",
+            profile.display_name
+        )])),
+        (false, _) => Ok(voting_params.voting_period),
     }
 }
 
-/// Returns the voting period duration based on the network.
-pub fn get_voting_period(network: Network) -> Duration {
-    match network {
-        Network::LocalNode => Duration::hours(1),
-        Network::Testnet => Duration::hours(12),
-        Network::Mainnet => Duration::hours(120),
+/// Checks that the given previous version is the version that the network is currently running.
+/// Returns the found problems, while failing queries are only reported as warnings.
+pub async fn check_running_version(
+    profile: &ChainProfile,
+    previous_version: &Version,
+) -> Vec<String> {
+    let mut problems: Vec<String> = Vec::new();
+
+    match upgrade::get_node_version(profile).await {
+        Ok(node_version) if !version::is_same_version(previous_version, node_version.as_str()) => {
+            problems.push(format!(
+                "previous version {} does not match the version {} running on {}",
+                previous_version, node_version, profile.display_name
+            ))
+        }
+        Ok(_) => {}
+        Err(e) => println!("WARNING: failed to query the running version: {}", e),
+    }
+
+    match upgrade::get_applied_plan_height(profile, previous_version.to_string().as_str()).await {
+        Ok(Some(height)) => println!(
+            "Upgrade to {} was applied at height {}",
+            previous_version, height
+        ),
+        Ok(None) => println!(
+            "WARNING: no applied upgrade plan found for {} on {}",
+            previous_version, profile.display_name
+        ),
+        Err(e) => println!("WARNING: failed to query the applied upgrade plan: {}", e),
+    }
+
+    problems
+}
+
+/// Placeholder for the features, if they cannot be taken from the changelog.
+const FEATURES_PLACEHOLDER: &str = "TODO: describe the features of this release";
+
+/// Returns the features of the target version from its changelog.
+/// If the changelog cannot be retrieved, a placeholder is returned, that has to be filled manually.
+pub async fn get_features(
+    repository: &Repository,
+    previous_version: &Version,
+    target_version: &Version,
+) -> Vec<String> {
+    get_features_or_placeholder(
+        changelog::get_features(repository, previous_version, target_version).await,
+        target_version,
+    )
+}
+
+/// Returns the given changelog features or the placeholder, if there are none.
+fn get_features_or_placeholder(
+    features: Result<Vec<String>, Error>,
+    target_version: &Version,
+) -> Vec<String> {
+    match features {
+        Ok(features) if !features.is_empty() => features,
+        Ok(_) => {
+            println!("WARNING: no changelog entries found for {}", target_version);
+            vec![FEATURES_PLACEHOLDER.to_string()]
+        }
+        Err(e) => {
+            println!("WARNING: failed to get the changelog entries: {}", e);
+            vec![FEATURES_PLACEHOLDER.to_string()]
+        }
+    }
+}
+
+/// Returns the upgrade plans that are currently scheduled or proposed on the given network.
+/// Failing queries are reported as warnings, since the conflicts cannot be checked in that case.
+pub async fn get_pending_plans(profile: &ChainProfile) -> Vec<PendingPlan> {
+    let mut pending_plans: Vec<PendingPlan> = Vec::new();
+
+    match upgrade::get_current_plan(profile).await {
+        Ok(Some(plan)) => pending_plans.push(PendingPlan::Scheduled(plan)),
+        Ok(None) => {}
+        Err(e) => println!("WARNING: failed to query the scheduled upgrade plan: {}", e),
+    }
+
+    match gov::get_proposed_plans(profile).await {
+        Ok(plans) => pending_plans.extend(plans),
+        Err(e) => println!("WARNING: failed to query the proposed upgrade plans: {}", e),
+    }
+
+    pending_plans
+}
+
+/// Returns the deposit parameters of the given network.
+/// If the query fails, empty deposit lists are returned and the minimum deposit is not checked.
+pub async fn get_deposit_params(profile: &ChainProfile) -> DepositParams {
+    match gov::get_deposit_params(profile).await {
+        Ok(params) => {
+            println!(
+                "Minimum deposit on {}: {}",
+                profile.display_name,
+                get_coins_string(&params.min_deposit)
+            );
+            if !params.expedited_min_deposit.is_empty() {
+                println!(
+                    "Expedited minimum deposit: {}",
+                    get_coins_string(&params.expedited_min_deposit)
+                );
+            }
+            params
+        }
+        Err(e) => {
+            match &profile.gov.deposit {
+                Some(deposit) => println!(
+                    "WARNING: failed to query the minimum deposit, using the default of {}: {}",
+                    deposit, e
+                ),
+                None => println!("WARNING: failed to query the minimum deposit: {}", e),
+            }
+            DepositParams {
+                min_deposit: Vec::new(),
+                expedited_min_deposit: Vec::new(),
+            }
+        }
+    }
+}
+
+/// Returns the comma separated representation of the given coins.
+fn get_coins_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|coin| coin.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::network::Network;
+    use chrono::TimeZone;
+
+    /// Returns an upgrade helper for testing purposes, which does not require any network queries.
+    /// The upgrade is prepared for the local node, which allows any target version,
+    /// on the given day of October 2023.
+    pub(crate) fn get_helper(
+        previous_version: &str,
+        target_version: &str,
+        day: u32,
+    ) -> UpgradeHelper {
+        let profile = ChainProfile::builtin(Network::LocalNode);
+        let mainnet_deposit = ChainProfile::builtin(Network::Mainnet).gov.deposit.unwrap();
+
+        UpgradeHelper {
+            previous_version: previous_version.parse().unwrap(),
+            target_version: target_version.parse().unwrap(),
+            proposal_name: "Evmos Local Node Upgrade".to_string(),
+            upgrade_time: Utc.with_ymd_and_hms(2023, 10, day, 16, 0, 0).unwrap(),
+            upgrade_type: UpgradeType::from_versions(
+                &previous_version.parse().unwrap(),
+                &target_version.parse().unwrap(),
+            ),
+            voting_period: Duration::hours(profile.gov.voting_period_hours),
+            expedited: false,
+            upgrade_height: 16_800_000,
+            estimation_method: "the average block time of 2.00 seconds over the last 50,000 blocks"
+                .to_string(),
+            upgrade_time_interval: None,
+            seconds_per_block: 2.0,
+            output_dir: PathBuf::from("."),
+            project: Project::default(),
+            features: vec![],
+            metadata: String::new(),
+            deposit: Some(mainnet_deposit.clone()),
+            min_deposit: vec![mainnet_deposit],
+            allow_conflicts: false,
+            template: None,
+            profile,
+        }
+    }
+
+    #[test]
+    fn test_round_upgrade_height_pass() {
+        let mut helper = get_helper("v14.0.0", "v15.0.0", 23);
+        helper.upgrade_height = 16_800_400;
+        let upgrade_time = helper.upgrade_time;
+
+        helper
+            .round_upgrade_height(1_000, Duration::minutes(30))
+            .expect("the height should be rounded");
+        assert_eq!(helper.upgrade_height, 16_800_000);
+        assert_eq!(
+            helper.upgrade_time,
+            upgrade_time - Duration::seconds(800),
+            "expected the upgrade time to be shifted by 400 blocks"
+        );
+    }
+
+    #[test]
+    fn test_round_upgrade_height_exceeds_drift() {
+        let mut helper = get_helper("v14.0.0", "v15.0.0", 23);
+        helper.upgrade_height = 16_804_000;
+
+        let err = helper
+            .round_upgrade_height(10_000, Duration::minutes(30))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("exceeds the maximum drift of 30 minutes"),
+            "unexpected error: {}",
+            err
+        );
+        assert_eq!(
+            helper.upgrade_height, 16_804_000,
+            "expected the height to be unchanged"
+        );
+    }
+
+    #[test]
+    fn test_round_upgrade_height_zero_granularity() {
+        let mut helper = get_helper("v14.0.0", "v15.0.0", 23);
+        assert!(helper
+            .round_upgrade_height(0, Duration::minutes(30))
+            .is_err());
+    }
+
+    #[test]
+    fn test_check_pass() {
+        let helper = get_helper("v14.0.0", "v15.0.0", 23);
+        assert_eq!(
+            helper.check(),
+            Vec::<String>::new(),
+            "expected a valid configuration"
+        );
+    }
+
+    #[test]
+    fn test_check_collects_all_problems() {
+        // NOTE: October 28th, 2023 is a Saturday
+        let mut helper = get_helper("v14.0.0", "v13.10.0", 28);
+        helper.deposit = None;
+        let problems = helper.check();
+        assert_eq!(problems.len(), 4, "expected all problems to be reported");
+        assert!(problems.contains(&NO_DEPOSIT.to_string()));
+    }
+
+    #[test]
+    fn test_get_voting_period() {
+        let profile = ChainProfile::builtin(Network::Mainnet);
+        let voting_params = VotingParams {
+            voting_period: Duration::hours(120),
+            expedited_voting_period: Some(Duration::hours(24)),
+        };
+        assert_eq!(
+            get_voting_period(&profile, &voting_params, false).unwrap(),
+            Duration::hours(120)
+        );
+        assert_eq!(
+            get_voting_period(&profile, &voting_params, true).unwrap(),
+            Duration::hours(24)
+        );
+
+        let voting_params = VotingParams {
+            expedited_voting_period: None,
+            ..voting_params
+        };
+        assert!(get_voting_period(&profile, &voting_params, true).is_err());
+    }
+
+    #[test]
+    fn test_get_features_or_placeholder() {
+        let version: Version = "v15.0.0".parse().unwrap();
+        assert_eq!(
+            get_features_or_placeholder(
+                Ok(vec!["Add the vesting precompile".to_string()]),
+                &version
+            ),
+            vec!["Add the vesting precompile"]
+        );
+        assert_eq!(
+            get_features_or_placeholder(Ok(vec![]), &version),
+            vec![FEATURES_PLACEHOLDER]
+        );
+        assert_eq!(
+            get_features_or_placeholder(Err(Error::Release("no changelog".to_string())), &version),
+            vec![FEATURES_PLACEHOLDER]
+        );
+    }
+
+    #[test]
+    fn test_get_deposit_warning() {
+        let mut helper = get_helper("v14.0.0", "v15.0.0", 23);
+        assert_eq!(helper.get_deposit_warning(), None);
+
+        helper.deposit = Some("1aevmos".parse().unwrap());
+        assert!(helper
+            .get_deposit_warning()
+            .is_some_and(|warning| warning.contains("below the minimum deposit")));
+
+        helper.min_deposit = Vec::new();
+        assert!(helper
+            .get_deposit_warning()
+            .is_some_and(|warning| warning.contains("could not be verified")));
     }
 }
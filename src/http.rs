@@ -14,13 +14,13 @@ mod tests {
     async fn test_get_pass() {
         let url = Url::parse("https://httpbin.org/get").unwrap();
         let resp = get(url).await.expect("the request should be successful");
-        assert_eq!(resp.status().is_success(), true);
+        assert!(resp.status().is_success());
     }
 
     #[tokio::test]
     async fn test_get_fail() {
         let url = Url::parse("https://invalidurl.org/get").unwrap();
         let res = get(url).await;
-        assert_eq!(res.is_err(), true);
+        assert!(res.is_err());
     }
 }
@@ -1,9 +1,7 @@
-use crate::network::Network;
-use chrono::{
-    DateTime, Datelike, Duration, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc, Weekday,
-};
+use crate::{error::Error, network::Network};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc, Weekday};
 use inquire::{DateSelect, Select};
-use std::{ops::Add, process};
+use std::ops::Add;
 
 const MONTHS: [&str; 13] = [
     "", "January", "February", "March", "April", "May", "June", "July", "August", "Septemer",
@@ -11,75 +9,41 @@ const MONTHS: [&str; 13] = [
 ];
 
 /// Prompts the user to select the network type used.
-pub fn get_used_network() -> Network {
-    let used_network: Network;
-
+pub fn get_used_network() -> Result<Network, Error> {
     let network_options = vec!["Local Node", "Testnet", "Mainnet"];
 
     // Prompt the user to select the network
-    let chosen_network = Select::new("Select network", network_options).prompt();
-
-    match chosen_network {
-        Ok(choice) => {
-            match choice {
-                "Local Node" => used_network = Network::LocalNode,
-                "Testnet" => used_network = Network::Testnet,
-                "Mainnet" => used_network = Network::Mainnet,
-                &_ => {
-                    println!("Invalid network selected: {:?}", choice);
-                    process::exit(1); // TODO: return error here instead of exiting in here
-                }
-            }
-        }
-        Err(e) => {
-            println!("Error selecting network: {}", e);
-            process::exit(1);
-        }
-    }
-
-    used_network
-}
+    let choice = Select::new("Select network", network_options).prompt()?;
 
-/// Prompts the user to input the target version to upgrade to.
-pub fn get_text(prompt: &str) -> String {
-    let target_version: String;
-    // Prompt the user to input the desired target version
-    let result = inquire::Text::new(prompt).prompt();
-    match result {
-        Ok(version) => {
-            target_version = version;
-        }
-        Err(e) => {
-            println!("Error selecting target version: {}", e);
-            process::exit(1);
-        }
+    match choice {
+        "Local Node" => Ok(Network::LocalNode),
+        "Testnet" => Ok(Network::Testnet),
+        "Mainnet" => Ok(Network::Mainnet),
+        &_ => Err(Error::Parse(format!("network selection: {:?}", choice))),
     }
+}
 
-    target_version
+/// Prompts the user to input a text value, e.g. the target version to upgrade to.
+pub fn get_text(prompt: &str) -> Result<String, Error> {
+    Ok(inquire::Text::new(prompt).prompt()?)
 }
 
 /// Prompts the user to input the date for the planned upgrade.
-/// The date is calculated based on the current time and the voting period duration.
-pub fn get_upgrade_date(voting_period: Duration, utc_time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+/// The default date is calculated based on the current time and the voting period duration.
+pub fn get_upgrade_date(
+    voting_period: Duration,
+    utc_time: DateTime<Utc>,
+) -> Result<NaiveDate, Error> {
     let default_date = calculate_planned_date(voting_period, utc_time);
 
     // Prompt the user to input the desired upgrade date
-    let result = DateSelect::new("Select date for the planned upgrade")
+    let date = DateSelect::new("Select date for the planned upgrade")
         .with_min_date(utc_time.date_naive())
         .with_default(default_date.date_naive())
         .with_week_start(Weekday::Mon)
-        .prompt();
-    match result {
-        Ok(date) => {
-            let time = NaiveTime::from_hms_opt(16, 0, 0).unwrap();
-            let planned_naive_date_time = NaiveDateTime::new(date, time);
-            Some(Utc.from_local_datetime(&planned_naive_date_time).unwrap())
-        }
-        Err(e) => {
-            println!("Error selecting planned date: {}", e);
-            return None;
-        }
-    }
+        .prompt()?;
+
+    Ok(date)
 }
 
 /// Calculates the date for the planned upgrade given the current time and the voting period duration.
@@ -111,6 +75,16 @@ fn calculate_planned_date(voting_period: Duration, utc_time: DateTime<Utc>) -> D
     .unwrap()
 }
 
+/// Holidays (month, day and name), on which no upgrade should be scheduled.
+/// These are only reported as warnings and do not invalidate the upgrade time.
+const HOLIDAYS: [(u32, u32, &str); 5] = [
+    (1, 1, "New Year's Day"),
+    (12, 24, "Christmas Eve"),
+    (12, 25, "Christmas Day"),
+    (12, 26, "Boxing Day"),
+    (12, 31, "New Year's Eve"),
+];
+
 /// Checks if the passed upgrade time is valid.
 /// The upgrade time cannot be on a weekend.
 pub fn is_valid_upgrade_time(upgrade_time: DateTime<Utc>) -> bool {
@@ -121,6 +95,28 @@ pub fn is_valid_upgrade_time(upgrade_time: DateTime<Utc>) -> bool {
     true
 }
 
+/// Returns the reasons, why the passed upgrade time is not suited for an upgrade.
+pub fn get_upgrade_time_warnings(upgrade_time: DateTime<Utc>) -> Vec<String> {
+    let mut warnings: Vec<String> = Vec::new();
+
+    if upgrade_time.weekday() == Weekday::Sat || upgrade_time.weekday() == Weekday::Sun {
+        warnings.push(format!("{} is on a weekend", upgrade_time.date_naive()));
+    }
+
+    if let Some((_, _, name)) = HOLIDAYS
+        .iter()
+        .find(|(month, day, _)| upgrade_time.month() == *month && upgrade_time.day() == *day)
+    {
+        warnings.push(format!(
+            "{} is a holiday ({})",
+            upgrade_time.date_naive(),
+            name
+        ));
+    }
+
+    warnings
+}
+
 /// Returns a string representation of the upgrade time.
 pub fn get_time_string(time: DateTime<Utc>) -> String {
     let (is_pm, hour) = time.hour12();
@@ -243,6 +239,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_upgrade_time_warnings() {
+        assert!(
+            get_upgrade_time_warnings(Utc.with_ymd_and_hms(2023, 10, 23, 16, 0, 0).unwrap())
+                .is_empty()
+        );
+        assert_eq!(
+            get_upgrade_time_warnings(Utc.with_ymd_and_hms(2023, 10, 28, 16, 0, 0).unwrap()),
+            vec!["2023-10-28 is on a weekend"],
+        );
+        assert_eq!(
+            get_upgrade_time_warnings(Utc.with_ymd_and_hms(2022, 12, 25, 16, 0, 0).unwrap()),
+            vec![
+                "2022-12-25 is on a weekend",
+                "2022-12-25 is a holiday (Christmas Day)"
+            ],
+        );
+        assert!(
+            is_valid_upgrade_time(Utc.with_ymd_and_hms(2023, 12, 26, 16, 0, 0).unwrap()),
+            "expected a holiday to only be reported as a warning"
+        );
+    }
+
     #[test]
     fn test_get_time_string_october_morning() {
         let time = Utc.with_ymd_and_hms(2023, 10, 23, 4, 0, 0).unwrap();
@@ -0,0 +1,47 @@
+//! Helper library to prepare software upgrades for the Evmos networks
+//! and other Cosmos SDK chains described by a [`profile::ChainProfile`].
+//!
+//! It contains the building blocks used by the interactive `upgrade-helper` CLI,
+//! so that they can be reused e.g. by release automation:
+//!
+//! - [`block`] estimates the block height for a planned upgrade time.
+//! - [`version`] validates the previous and target versions against a version policy.
+//! - [`proposal`] renders the proposal description for an [`UpgradeHelper`].
+//! - [`release`] looks up the GitHub release of a version.
+//! - [`watch`] monitors the estimated time of an already scheduled upgrade height.
+//!
+//! ```no_run
+//! use chrono::{Duration, Utc};
+//! use upgrade_helper::{block::get_estimated_height, profile::ChainProfile, Error, Network};
+//!
+//! # async fn example() -> Result<(), Error> {
+//! let upgrade_time = Utc::now() + Duration::days(5);
+//! let estimate = get_estimated_height(&ChainProfile::builtin(Network::Mainnet), upgrade_time).await?;
+//! println!("Estimated upgrade height: {}", estimate.height);
+//! println!("Based on {}", estimate.block_time.method());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod block;
+pub mod changelog;
+pub mod config;
+pub mod error;
+pub mod gov;
+pub mod helper;
+mod http;
+pub mod inputs;
+pub mod network;
+pub mod profile;
+pub mod project;
+pub mod proposal;
+pub mod registry;
+pub mod release;
+pub mod rest;
+pub mod upgrade;
+pub mod version;
+pub mod watch;
+
+pub use error::Error;
+pub use helper::UpgradeHelper;
+pub use network::Network;
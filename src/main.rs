@@ -1,71 +1,257 @@
-mod block;
-mod helper;
-mod http;
-mod inputs;
-mod network;
-mod proposal;
-mod release;
-mod version;
-
-use chrono::{DateTime, Utc};
-use helper::UpgradeHelper;
-use std::process;
-
-/// Creates a new instance of the upgrade helper based on querying the user for the necessary input.
-async fn get_helper_from_inputs() -> UpgradeHelper {
-    // Query and check the network to use
-    let used_network = inputs::get_used_network();
+mod cli;
 
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use clap::Parser;
+use cli::{Args, Command};
+use std::{path::PathBuf, process};
+use upgrade_helper::{
+    block, config, config::UpgradeConfig, helper, inputs, profile, profile::ChainProfile,
+    project::Project, proposal, registry, rest, version, version::Version, watch, Error,
+    UpgradeHelper,
+};
+
+/// Maximum shift of the upgrade time in minutes, that is accepted when rounding the upgrade height.
+const DEFAULT_MAX_DRIFT_MINUTES: i64 = 30;
+
+/// Exit code of the `watch` command, if the upgrade time drifted too far from the announced time.
+const DRIFT_EXIT_CODE: i32 = 2;
+
+/// Prompts the user to input a version and parses it.
+fn get_version(prompt: &str) -> Result<Version, Error> {
+    let version = inputs::get_text(prompt)?;
+    version.parse().map_err(|e| Error::Validation(vec![e]))
+}
+
+/// Creates a new instance of the upgrade helper based on the passed command line arguments.
+/// The user is queried for all necessary inputs that were not passed as flags.
+async fn get_helper_from_inputs(
+    args: Args,
+    profile: ChainProfile,
+    project: Project,
+) -> Result<UpgradeHelper, Error> {
     // Query and check the version to upgrade from
-    let previous_version = inputs::get_text("Previous version to upgrade from:");
-    let valid_version = version::is_valid_version(previous_version.as_str());
-    if !valid_version {
-        println!("Invalid previous version: {}", previous_version);
-        process::exit(1);
-    }
+    let previous_version = match args.previous_version {
+        Some(version) => version,
+        None => get_version("Previous version to upgrade from:")?,
+    };
 
     // Query and check the target version to upgrade to
-    let target_version = inputs::get_text("Target version to upgrade to:");
-    let valid_version = version::is_valid_target_version(used_network, target_version.as_str());
+    let target_version = match args.target_version {
+        Some(version) => version,
+        None => get_version("Target version to upgrade to:")?,
+    };
+    let valid_version =
+        version::is_valid_target_version(&profile.version_policy(), &target_version);
     if !valid_version {
-        println!(
-            "Invalid target version for {}: {}",
-            used_network, target_version
-        );
-        process::exit(1);
+        return Err(Error::Validation(vec![format!(
+            "invalid target version for {}: {}",
+            profile.display_name, target_version
+        )]));
     }
 
     // Query the date and time for the upgrade
-    let upgrade_time: DateTime<Utc>;
-    let voting_period = helper::get_voting_period(used_network);
-    let time_option = inputs::get_upgrade_date(voting_period, Utc::now());
-    match time_option {
-        Some(time) => {
-            upgrade_time = time;
-        }
+    let upgrade_date = match args.upgrade_date {
+        Some(date) => date,
         None => {
-            process::exit(1);
+            let voting_params = helper::get_voting_params(&profile).await;
+            let voting_period =
+                helper::get_voting_period(&profile, &voting_params, args.expedited)?;
+            inputs::get_upgrade_date(voting_period, Utc::now())?
         }
-    }
+    };
+    let upgrade_time: DateTime<Utc> =
+        Utc.from_utc_datetime(&NaiveDateTime::new(upgrade_date, args.upgrade_time));
 
     // Create an instance of the helper
     UpgradeHelper::new(
-        used_network,
-        previous_version.as_str(),
-        target_version.as_str(),
+        profile,
+        previous_version,
+        target_version,
         upgrade_time,
+        args.expedited,
+        project,
+        args.output,
     )
     .await
 }
 
-#[tokio::main]
-async fn main() {
+/// Creates a new instance of the upgrade helper based on the given upgrade configuration.
+async fn get_helper_from_config(
+    config: UpgradeConfig,
+    profile: ChainProfile,
+    project: Project,
+    expedited: bool,
+    output_dir: PathBuf,
+) -> Result<UpgradeHelper, Error> {
+    let mut upgrade_helper = UpgradeHelper::new(
+        profile,
+        config.previous_version,
+        config.target_version,
+        config.upgrade_time,
+        expedited || config.expedited,
+        project,
+        output_dir,
+    )
+    .await?;
+
+    if let Some(hours) = config.voting_period_hours {
+        upgrade_helper.voting_period = Duration::hours(hours);
+    }
+    if let Some(features) = config.features {
+        upgrade_helper.features = features;
+    }
+    if let Some(metadata) = config.metadata {
+        upgrade_helper.metadata = metadata;
+    }
+    if let Some(deposit) = config.deposit {
+        upgrade_helper.deposit = Some(deposit);
+    }
+    if let Some(template) = config.template {
+        upgrade_helper.template = Some(template);
+    }
+    if let Some(template_dir) = config.template_dir {
+        upgrade_helper.template = Some(proposal::find_template(
+            &template_dir,
+            &upgrade_helper.profile,
+        )?);
+    }
+
+    Ok(upgrade_helper)
+}
+
+/// Estimates the time, at which the given block height is reached, and prints it
+/// together with the warnings for scheduling an upgrade at that time.
+async fn print_estimated_time(profile: ChainProfile, height: u64) -> Result<(), Error> {
+    let estimate = block::get_estimated_time(&profile, height).await?;
+
+    println!(
+        "Block {} of {} is estimated to be reached at {} (based on {})",
+        height,
+        profile.display_name,
+        estimate.time.format("%Y-%m-%d %H:%M:%S UTC"),
+        estimate.block_time.method()
+    );
+    if let Some(interval) = &estimate.time_interval {
+        println!("  {}", proposal::get_time_interval_string(interval));
+    }
+    for warning in inputs::get_upgrade_time_warnings(estimate.time) {
+        println!("WARNING: {}", warning);
+    }
+
+    Ok(())
+}
+
+/// Runs the upgrade helper with the passed command line arguments.
+async fn run(args: Args) -> Result<(), Error> {
+    let deposit = args.deposit.clone();
+    let allow_conflicts = args.allow_conflicts;
+    let template = args.template.clone();
+    let template_dir = args.template_dir.clone();
+
+    let config = match args.config {
+        Some(ref path) => Some(config::load_config(path)?),
+        None => None,
+    };
+
+    // The rounding flags take precedence over the configured rounding
+    let round_to = args
+        .round_to
+        .or(config.as_ref().and_then(|config| config.round_to));
+    let max_drift_minutes = args
+        .max_drift_minutes
+        .or(config.as_ref().and_then(|config| config.max_drift_minutes))
+        .unwrap_or(DEFAULT_MAX_DRIFT_MINUTES);
+
+    // Select the chain profile from the configuration, a profile file or the built-in networks
+    let mut profile = match (&config, &args.profile, &args.chain_registry) {
+        (Some(config), _, _) => config.chain_profile()?,
+        (None, Some(path), _) => match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => registry::load_chain_file(path)?,
+            _ => profile::load_profile(path)?,
+        },
+        (None, None, Some(registry_dir)) => match &args.chain {
+            Some(chain) => registry::load_chain(registry_dir, chain)?,
+            None => registry::load_chain(registry_dir, inputs::get_text("Chain name:")?.as_str())?,
+        },
+        (None, None, None) => match args.network {
+            Some(network) => ChainProfile::builtin(network),
+            None => ChainProfile::builtin(inputs::get_used_network()?),
+        },
+    };
+    if let Some(repository) = args.repository.clone() {
+        profile.repository = repository;
+    }
+    if let Some(block_source) = args.block_source {
+        profile.block_source = block_source;
+    }
+    // NOTE: the watch command checks the health of the endpoints itself, as it runs for a long time
+    if let Some(Command::Watch(watch_args)) = &args.command {
+        return watch::watch(
+            profile,
+            watch_args.height,
+            watch_args.announced_time,
+            Duration::minutes(watch_args.max_drift_minutes),
+            Duration::minutes(watch_args.interval_minutes),
+        )
+        .await;
+    }
+    let profile = rest::check_endpoints(profile).await;
+    if let Some(height) = args.height {
+        return print_estimated_time(profile, height).await;
+    }
+
+    // The project flags take precedence over the configured project
+    let mut project = config
+        .as_ref()
+        .map(|config| config.project.clone())
+        .unwrap_or_default();
+    if let Some(author) = args.author.clone() {
+        project.author = author;
+    }
+
     // Create an instance of the helper
-    let upgrade_helper = get_helper_from_inputs().await;
+    let mut upgrade_helper = match config {
+        Some(config) => {
+            get_helper_from_config(config, profile, project, args.expedited, args.output).await?
+        }
+        None => get_helper_from_inputs(args, profile, project).await?,
+    };
+    if let Some(deposit) = deposit {
+        upgrade_helper.deposit = Some(deposit);
+    }
+    upgrade_helper.allow_conflicts = allow_conflicts;
+    if let Some(template) = template {
+        upgrade_helper.template = Some(template);
+    }
+    if let Some(template_dir) = template_dir {
+        upgrade_helper.template = Some(proposal::find_template(
+            &template_dir,
+            &upgrade_helper.profile,
+        )?);
+    }
+    if let Some(granularity) = round_to {
+        upgrade_helper.round_upgrade_height(granularity, Duration::minutes(max_drift_minutes))?;
+    }
 
     // Validate the helper configuration
-    upgrade_helper.validate();
+    upgrade_helper.validate().await?;
 
     // Run the main functionality of the helper.
-    upgrade_helper.run().await;
+    upgrade_helper.run().await
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    if let Err(e) = args.check_conflicts() {
+        e.exit();
+    }
+
+    if let Err(e) = run(args).await {
+        println!("Error: {}", e);
+        match e {
+            Error::Drift(_) => process::exit(DRIFT_EXIT_CODE),
+            _ => process::exit(1),
+        }
+    }
 }
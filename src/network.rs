@@ -1,7 +1,9 @@
-use std::fmt;
+use serde::Deserialize;
+use std::{fmt, str::FromStr};
 
-// Enum to represent different network options
-#[derive(Clone, Copy, Debug)]
+/// Enum to represent different network options
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(try_from = "String")]
 pub enum Network {
     LocalNode,
     Testnet,
@@ -17,3 +19,45 @@ impl fmt::Display for Network {
         }
     }
 }
+
+impl FromStr for Network {
+    type Err = String;
+
+    /// Parses the network from the given string, e.g. when passed as a command line flag.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" | "localnode" | "local-node" => Ok(Network::LocalNode),
+            "testnet" => Ok(Network::Testnet),
+            "mainnet" => Ok(Network::Mainnet),
+            _ => Err(format!(
+                "invalid network: {}; expected one of local-node, testnet, mainnet",
+                s
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Network {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str_pass() {
+        assert_eq!("local-node".parse::<Network>(), Ok(Network::LocalNode));
+        assert_eq!("Testnet".parse::<Network>(), Ok(Network::Testnet));
+        assert_eq!("mainnet".parse::<Network>(), Ok(Network::Mainnet));
+    }
+
+    #[test]
+    fn test_from_str_fail() {
+        assert!("devnet".parse::<Network>().is_err());
+    }
+}
@@ -0,0 +1,374 @@
+use crate::{
+    block::BlockSource, error::Error, gov::Coin, network::Network, project::Repository,
+    version::VersionPolicy,
+};
+use serde::Deserialize;
+use std::path::Path;
+use url::Url;
+
+/// Describes a network of a Cosmos SDK chain, for which upgrades can be prepared.
+/// The Evmos networks are shipped as built-in profiles (see [`ChainProfile::builtin`]),
+/// while other chains can be described in a TOML file:
+///
+/// ```toml
+/// name = "gaia-mainnet"
+/// network = "mainnet"
+/// chain_name = "Cosmos Hub"
+/// display_name = "Cosmos Hub Mainnet"
+/// chain_id = "cosmoshub-4"
+/// rest_endpoints = ["https://rest.cosmos.directory/cosmoshub", "https://cosmos-rest.publicnode.com"]
+/// rpc_endpoint = "https://rpc.cosmos.directory/cosmoshub"
+/// block_source = "rest"
+/// explorer = "https://www.mintscan.io/cosmos/blocks"
+/// repository = "cosmos/gaia"
+/// daemon = "gaiad"
+/// gov_authority = "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn"
+///
+/// [gov]
+/// voting_period_hours = 336
+/// deposit = "250000000uatom"
+///
+/// [version_policy]
+/// release_candidate = "forbidden"
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainProfile {
+    /// Identifier of the profile, which is used for the file names, e.g. `evmos-mainnet`.
+    pub name: String,
+    /// Type of the network, which determines the defaults of the version policy.
+    pub network: Network,
+    pub chain_name: String,
+    pub display_name: String,
+    pub chain_id: String,
+    /// REST endpoints in the order of preference, see [`crate::rest::get`].
+    pub rest_endpoints: Vec<String>,
+    pub rpc_endpoint: String,
+    /// Source of the block data used to estimate the upgrade height, defaults to the REST endpoints.
+    #[serde(default)]
+    pub block_source: BlockSource,
+    /// Base URL of the block explorer, to which the block height is appended.
+    pub explorer: String,
+    pub repository: Repository,
+    /// Name of the chain binary, which is used to submit the proposal.
+    pub daemon: String,
+    /// Address of the governance module account, which is the authority to schedule software upgrades.
+    pub gov_authority: String,
+    pub gov: GovDefaults,
+    #[serde(default)]
+    pub version_policy: Option<VersionPolicy>,
+}
+
+/// Governance parameters, that are used if they cannot be queried from the network.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GovDefaults {
+    pub voting_period_hours: i64,
+    /// Deposit used if the minimum deposit cannot be queried; if not set, the deposit must be passed explicitly.
+    #[serde(default)]
+    pub deposit: Option<Coin>,
+}
+
+impl ChainProfile {
+    /// Returns the built-in profile of the given Evmos network.
+    pub fn builtin(network: Network) -> ChainProfile {
+        let (name, chain_id, rest_endpoints, rpc_endpoint, explorer) = match network {
+            Network::LocalNode => (
+                "evmos-local-node",
+                "evmos_9000-1",
+                vec!["http://localhost:1317"],
+                "http://localhost:26657",
+                "https://www.mintscan.io/evmos/blocks",
+            ),
+            Network::Testnet => (
+                "evmos-testnet",
+                "evmos_9000-4",
+                vec![
+                    "https://rest.evmos-testnet.lava.build",
+                    "https://evmos-testnet-api.polkachu.com",
+                ],
+                "https://tm.evmos-testnet.lava.build",
+                "https://testnet.mintscan.io/evmos-testnet/blocks",
+            ),
+            Network::Mainnet => (
+                "evmos-mainnet",
+                "evmos_9001-2",
+                vec![
+                    "https://rest.evmos.lava.build",
+                    "https://evmos-rest.publicnode.com",
+                    "https://evmos-api.polkachu.com",
+                ],
+                "https://tm.evmos.lava.build",
+                "https://www.mintscan.io/evmos/blocks",
+            ),
+        };
+        let gov = match network {
+            Network::LocalNode => GovDefaults {
+                voting_period_hours: 1,
+                deposit: Some(Coin {
+                    amount: 10_000_000_000_000_000_000,
+                    denom: "aevmos".to_string(),
+                }),
+            },
+            Network::Testnet => GovDefaults {
+                voting_period_hours: 12,
+                deposit: Some(Coin {
+                    amount: 10_000_000_000_000_000_000,
+                    denom: "atevmos".to_string(),
+                }),
+            },
+            Network::Mainnet => GovDefaults {
+                voting_period_hours: 120,
+                deposit: Some(Coin {
+                    amount: 2_000_000_000_000_000_000_000,
+                    denom: "aevmos".to_string(),
+                }),
+            },
+        };
+
+        ChainProfile {
+            name: name.to_string(),
+            network,
+            chain_name: "Evmos".to_string(),
+            display_name: format!("Evmos {}", network),
+            chain_id: chain_id.to_string(),
+            rest_endpoints: rest_endpoints.into_iter().map(String::from).collect(),
+            rpc_endpoint: rpc_endpoint.to_string(),
+            block_source: BlockSource::Rest,
+            explorer: explorer.to_string(),
+            repository: Repository::default(),
+            daemon: "evmosd".to_string(),
+            gov_authority: "evmos10d07y265gmmuvt4z0w9aw880jnsr700jcrztvm".to_string(),
+            gov,
+            // NOTE: Evmos uses single digit minor versions
+            version_policy: Some(VersionPolicy {
+                max_minor: Some(9),
+                ..VersionPolicy::for_network(network)
+            }),
+        }
+    }
+
+    /// Returns the version policy of the profile, which defaults to the policy of the network type.
+    pub fn version_policy(&self) -> VersionPolicy {
+        self.version_policy
+            .clone()
+            .unwrap_or(VersionPolicy::for_network(self.network))
+    }
+
+    /// Returns the link to the block with the given height on the block explorer.
+    pub fn block_url(&self, height: u64) -> String {
+        format!("{}/{}", self.explorer.trim_end_matches('/'), height)
+    }
+
+    /// Builds the URL for the given endpoint of the CometBFT RPC.
+    pub fn rpc_url(&self, endpoint: &str) -> Result<Url, url::ParseError> {
+        get_endpoint_url(self.rpc_endpoint.as_str(), endpoint)
+    }
+
+    /// Checks the profile values for consistency.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.rest_endpoints.is_empty() {
+            return Err(Error::Config(
+                "invalid value for key `rest_endpoints`: at least one endpoint is required"
+                    .to_string(),
+            ));
+        }
+
+        let rest_endpoints = self
+            .rest_endpoints
+            .iter()
+            .map(|endpoint| ("rest_endpoints", endpoint));
+        for (key, value) in rest_endpoints.chain([
+            ("rpc_endpoint", &self.rpc_endpoint),
+            ("explorer", &self.explorer),
+        ]) {
+            Url::parse(value).map_err(|e| {
+                Error::Config(format!("invalid value for key `{}`: {}; {}", key, value, e))
+            })?;
+        }
+
+        if self.gov.voting_period_hours <= 0 {
+            return Err(Error::Config(format!(
+                "invalid value for key `gov.voting_period_hours`: {}; must be positive",
+                self.gov.voting_period_hours
+            )));
+        }
+
+        if let Some(deposit) = &self.gov.deposit {
+            if deposit.amount == 0 {
+                return Err(Error::Config(format!(
+                    "invalid value for key `gov.deposit`: {}; must be positive",
+                    deposit
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Joins the endpoint to the base URL.
+/// The base URL is treated as a directory, so that a path of the base URL is kept.
+pub(crate) fn get_endpoint_url(base_url: &str, endpoint: &str) -> Result<Url, url::ParseError> {
+    Url::parse(format!("{}/", base_url.trim_end_matches('/')).as_str())?.join(endpoint)
+}
+
+/// Parses the chain profile from the given TOML contents.
+pub fn parse_profile(contents: &str) -> Result<ChainProfile, Error> {
+    let profile: ChainProfile =
+        toml::from_str(contents).map_err(|e| Error::Config(e.to_string()))?;
+    profile.validate()?;
+
+    Ok(profile)
+}
+
+/// Loads the chain profile from the TOML file at the given path.
+pub fn load_profile(path: &Path) -> Result<ChainProfile, Error> {
+    let contents = std::fs::read_to_string(path)?;
+
+    parse_profile(contents.as_str()).map_err(|e| match e {
+        Error::Config(msg) => Error::Config(format!("{}: {}", path.display(), msg)),
+        e => e,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::ReleaseCandidatePolicy;
+
+    const PROFILE: &str = r#"
+        name = "gaia-mainnet"
+        network = "mainnet"
+        chain_name = "Cosmos Hub"
+        display_name = "Cosmos Hub Mainnet"
+        chain_id = "cosmoshub-4"
+        rest_endpoints = ["https://rest.cosmos.directory/cosmoshub"]
+        rpc_endpoint = "https://rpc.cosmos.directory/cosmoshub"
+        explorer = "https://www.mintscan.io/cosmos/blocks"
+        repository = "cosmos/gaia"
+        daemon = "gaiad"
+        gov_authority = "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn"
+
+        [gov]
+        voting_period_hours = 336
+        deposit = "250000000uatom"
+    "#;
+
+    #[test]
+    fn test_parse_profile_pass() {
+        let profile = parse_profile(PROFILE).expect("the profile should be parsed");
+        assert_eq!(profile.network, Network::Mainnet);
+        assert_eq!(profile.chain_id, "cosmoshub-4");
+        assert_eq!(profile.repository.to_string(), "cosmos/gaia");
+        assert_eq!(profile.gov.deposit, Some("250000000uatom".parse().unwrap()));
+        assert_eq!(profile.block_source, BlockSource::Rest);
+        assert_eq!(
+            profile.version_policy(),
+            VersionPolicy::for_network(Network::Mainnet),
+            "expected the default policy of the network type"
+        );
+    }
+
+    #[test]
+    fn test_parse_profile_version_policy() {
+        let profile = parse_profile(&format!(
+            "{}\n[version_policy]\nrelease_candidate = \"allowed\"\nmax_minor = 20",
+            PROFILE
+        ))
+        .expect("the profile should be parsed");
+        assert_eq!(
+            profile.version_policy(),
+            VersionPolicy {
+                release_candidate: ReleaseCandidatePolicy::Allowed,
+                max_minor: Some(20),
+                require_tested_release_candidate: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_profile_block_source() {
+        let profile = parse_profile(&format!("block_source = \"rpc\"\n{}", PROFILE))
+            .expect("the profile should be parsed");
+        assert_eq!(profile.block_source, BlockSource::Rpc);
+
+        assert!(
+            parse_profile(&format!("block_source = \"grpc\"\n{}", PROFILE)).is_err(),
+            "expected an error for an unknown block source"
+        );
+    }
+
+    #[test]
+    fn test_parse_profile_invalid_endpoint() {
+        let err =
+            parse_profile(&PROFILE.replace("https://rest.cosmos.directory", "rest")).unwrap_err();
+        assert!(
+            err.to_string().contains("rest_endpoint"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_profile_zero_deposit() {
+        let err = parse_profile(&PROFILE.replace("250000000uatom", "0uatom")).unwrap_err();
+        assert!(
+            err.to_string().contains("gov.deposit"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_parse_profile_without_deposit() {
+        let profile = parse_profile(&PROFILE.replace("deposit = \"250000000uatom\"", ""))
+            .expect("the profile should be parsed");
+        assert_eq!(profile.gov.deposit, None);
+    }
+
+    #[test]
+    fn test_builtin_profiles() {
+        let mainnet = ChainProfile::builtin(Network::Mainnet);
+        assert_eq!(mainnet.display_name, "Evmos Mainnet");
+        assert_eq!(mainnet.chain_id, "evmos_9001-2");
+        assert!(mainnet.validate().is_ok());
+
+        let testnet = ChainProfile::builtin(Network::Testnet);
+        assert_eq!(testnet.gov.deposit.as_ref().unwrap().denom, "atevmos");
+        assert_eq!(testnet.version_policy().max_minor, Some(9));
+    }
+
+    #[test]
+    fn test_parse_profile_without_rest_endpoints() {
+        let err =
+            parse_profile(&PROFILE.replace(r#"["https://rest.cosmos.directory/cosmoshub"]"#, "[]"))
+                .unwrap_err();
+        assert!(
+            err.to_string().contains("rest_endpoints"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_get_endpoint_url_keeps_base_path() {
+        assert_eq!(
+            get_endpoint_url(
+                "https://rest.cosmos.directory/cosmoshub",
+                "cosmos/gov/v1/params/voting"
+            )
+            .unwrap()
+            .as_str(),
+            "https://rest.cosmos.directory/cosmoshub/cosmos/gov/v1/params/voting"
+        );
+    }
+
+    #[test]
+    fn test_block_url() {
+        assert_eq!(
+            ChainProfile::builtin(Network::Testnet).block_url(100),
+            "https://testnet.mintscan.io/evmos-testnet/blocks/100"
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{network::Network, profile::ChainProfile, version::Version};
+use serde::Deserialize;
+use std::{fmt, str::FromStr};
+
+/// Describes the project an upgrade is prepared for, i.e. who proposes the upgrade
+/// and which documentation is linked in the proposal.
+/// The defaults refer to the Evmos documentation.
+///
+/// ```toml
+/// [project]
+/// author = "Evmos Core Team"
+/// upgrade_guide = "https://docs.evmos.org/validate/upgrades"
+/// e2e_guide = "https://github.com/evmos/evmos/blob/main/tests/e2e/README.md"
+/// ```
+///
+/// The keys `repository`, `mainnet_explorer` and `testnet_explorer` of earlier versions
+/// are still accepted and override the chain profile (see [`Project::apply_legacy_keys`]).
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Project {
+    pub author: String,
+    pub upgrade_guide: String,
+    pub e2e_guide: String,
+    /// Deprecated in favor of the `repository` of the chain profile.
+    pub repository: Option<Repository>,
+    /// Deprecated in favor of the `explorer` of the mainnet chain profile.
+    pub mainnet_explorer: Option<String>,
+    /// Deprecated in favor of the `explorer` of the testnet chain profile.
+    pub testnet_explorer: Option<String>,
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Project {
+            author: "Malte Herrmann, Evmos Core Team".to_string(),
+            upgrade_guide: "https://docs.evmos.org/validate/upgrades".to_string(),
+            e2e_guide: "https://github.com/evmos/evmos/blob/main/tests/e2e/README.md".to_string(),
+            repository: None,
+            mainnet_explorer: None,
+            testnet_explorer: None,
+        }
+    }
+}
+
+impl Project {
+    /// Moves the configured legacy keys onto the given chain profile.
+    /// As before, the local node uses the mainnet explorer.
+    pub fn apply_legacy_keys(&self, profile: &mut ChainProfile) {
+        if let Some(repository) = &self.repository {
+            profile.repository = repository.clone();
+        }
+
+        let explorer = match profile.network {
+            Network::LocalNode | Network::Mainnet => &self.mainnet_explorer,
+            Network::Testnet => &self.testnet_explorer,
+        };
+        if let Some(explorer) = explorer {
+            profile.explorer = explorer.clone();
+        }
+    }
+}
+
+/// Represents a GitHub repository, e.g. `evmos/evmos`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(try_from = "String")]
+pub struct Repository {
+    pub owner: String,
+    pub name: String,
+}
+
+impl Default for Repository {
+    fn default() -> Self {
+        Repository {
+            owner: "evmos".to_string(),
+            name: "evmos".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Repository {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+impl FromStr for Repository {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+                Ok(Repository {
+                    owner: owner.to_string(),
+                    name: name.to_string(),
+                })
+            }
+            _ => Err(format!(
+                "invalid repository {}: expected the format `owner/name`",
+                s
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Repository {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Repository {
+    /// Returns the URL of the repository on GitHub.
+    pub fn url(&self) -> String {
+        format!("https://github.com/{}", self)
+    }
+
+    /// Returns the URL of the release page for the given version.
+    pub fn release_url(&self, version: &Version) -> String {
+        format!("{}/releases/tag/{}", self.url(), version)
+    }
+
+    /// Returns the URL comparing the code of the two given versions.
+    pub fn compare_url(&self, previous_version: &Version, target_version: &Version) -> String {
+        format!(
+            "{}/compare/{}..{}",
+            self.url(),
+            previous_version,
+            target_version
+        )
+    }
+
+    /// Returns the URL of the raw contents of the file at the tag of the given version.
+    pub fn raw_file_url(&self, version: &Version, path: &str) -> String {
+        format!(
+            "https://raw.githubusercontent.com/{}/{}/{}",
+            self, version, path
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repository() {
+        let repository: Repository = "cosmos/gaia".parse().unwrap();
+        assert_eq!(repository.owner, "cosmos");
+        assert_eq!(repository.name, "gaia");
+        assert_eq!(repository.to_string(), "cosmos/gaia");
+    }
+
+    #[test]
+    fn test_parse_repository_fail() {
+        for input in ["gaia", "cosmos/", "/gaia", "cosmos/gaia/x"] {
+            assert!(
+                input.parse::<Repository>().is_err(),
+                "expected an error for {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_repository_urls() {
+        let repository = Repository::default();
+        let previous: Version = "v14.0.0".parse().unwrap();
+        let target: Version = "v15.0.0".parse().unwrap();
+        assert_eq!(
+            repository.release_url(&target),
+            "https://github.com/evmos/evmos/releases/tag/v15.0.0"
+        );
+        assert_eq!(
+            repository.compare_url(&previous, &target),
+            "https://github.com/evmos/evmos/compare/v14.0.0..v15.0.0"
+        );
+        assert_eq!(
+            repository.raw_file_url(&target, "CHANGELOG.md"),
+            "https://raw.githubusercontent.com/evmos/evmos/v15.0.0/CHANGELOG.md"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_project_defaults() {
+        let project: Project = toml::from_str("author = \"Gaia Team\"").unwrap();
+        assert_eq!(project.author, "Gaia Team");
+        assert_eq!(project.upgrade_guide, Project::default().upgrade_guide);
+    }
+
+    #[test]
+    fn test_apply_legacy_keys() {
+        let project: Project = toml::from_str(
+            r#"
+            repository = "cosmos/gaia"
+            mainnet_explorer = "https://explorer.com/mainnet/blocks"
+            testnet_explorer = "https://explorer.com/testnet/blocks"
+            "#,
+        )
+        .expect("the legacy keys should be accepted");
+
+        let mut mainnet = ChainProfile::builtin(Network::Mainnet);
+        project.apply_legacy_keys(&mut mainnet);
+        assert_eq!(mainnet.repository.to_string(), "cosmos/gaia");
+        assert_eq!(mainnet.explorer, "https://explorer.com/mainnet/blocks");
+
+        let mut testnet = ChainProfile::builtin(Network::Testnet);
+        project.apply_legacy_keys(&mut testnet);
+        assert_eq!(testnet.explorer, "https://explorer.com/testnet/blocks");
+    }
+}
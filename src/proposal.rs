@@ -1,74 +1,282 @@
-use crate::{block::N_BLOCKS, helper::UpgradeHelper, inputs::get_time_string, network::Network};
-use handlebars::{Handlebars, RenderError};
+use crate::{
+    block::{ConfidenceInterval, CONFIDENCE_LEVEL},
+    error::Error,
+    gov::Coin,
+    helper::UpgradeHelper,
+    inputs::get_time_string,
+    network::Network,
+    profile::ChainProfile,
+    project::Repository,
+    version::Version,
+};
+use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext};
 use num_format::ToFormattedString;
-use serde_json::json;
+use serde_json::{json, Value};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Default proposal template, which is embedded in the binary.
+pub const DEFAULT_TEMPLATE: &str = include_str!("templates/proposal.hbs");
 
 /// Prepares the proposal text by filling in the necessary information
 /// to the proposal template.
-pub fn prepare_proposal(helper: &UpgradeHelper) -> Result<String, RenderError> {
-    let mut handlebars = Handlebars::new();
-    handlebars.set_strict_mode(true);
-
-    handlebars
-        .register_template_file("proposal", "src/templates/proposal.hbs")
-        .unwrap();
+/// The template configured on the helper is used if set, otherwise the embedded default template.
+pub fn prepare_proposal(helper: &UpgradeHelper) -> Result<String, Error> {
+    let template = match &helper.template {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!("failed to read template {}: {}", path.display(), e))
+        })?,
+        None => DEFAULT_TEMPLATE.to_string(),
+    };
 
-    let height_link = get_height_with_link(helper.network, helper.upgrade_height);
-    let n_blocks = N_BLOCKS.to_formatted_string(&num_format::Locale::en);
+    let height_link = get_height_with_link(&helper.profile, helper.upgrade_height);
 
     let data = json!({
-        "author": "Malte Herrmann, Evmos Core Team",
-        "diff_link": format!("https://github.com/evmos/evmos/compare/{}..{}",
-            helper.previous_version,
-            helper.target_version,
+        "author": helper.project.author,
+        "chain": helper.profile.chain_name,
+        "chain_id": helper.profile.chain_id,
+        "diff_link": helper.profile.repository.compare_url(
+            &helper.previous_version,
+            &helper.target_version,
         ),
+        "e2e_guide": helper.project.e2e_guide,
         "estimated_time": get_time_string(helper.upgrade_time),
-        "features": "- neue Features",
+        "features": get_features_list(&helper.features),
         "height": height_link,
         "name": helper.proposal_name,
-        "n_blocks": n_blocks,
-        "network": format!("{}", helper.network), // TODO: implement serialize trait here?
-        "previous_version": get_release_md_link(helper.previous_version.as_str()),
-        "version": get_release_md_link(helper.target_version.as_str()),
+        "estimation_method": helper.estimation_method,
+        "time_interval": helper.upgrade_time_interval.as_ref().map(get_time_interval_string),
+        "network": helper.profile.display_name,
+        "upgrade_type": helper.upgrade_type.to_string(),
+        "expedited": helper.expedited,
+        "previous_version": get_release_md_link(&helper.profile.repository, &helper.previous_version),
+        "upgrade_guide": helper.project.upgrade_guide,
+        "version": get_release_md_link(&helper.profile.repository, &helper.target_version),
         "voting_time": helper.voting_period.num_hours(),
     });
 
-    handlebars.render("proposal", &data)
+    render_template(template.as_str(), &data)
+}
+
+/// Returns the description of the time interval, in which the upgrade height is expected
+/// to be reached, e.g. `between 15:42 and 16:19 UTC with 90% confidence`.
+/// The dates are included if the interval spans multiple days.
+pub fn get_time_interval_string(interval: &ConfidenceInterval) -> String {
+    let time_format = match interval.earliest.date_naive() == interval.latest.date_naive() {
+        true => "%H:%M",
+        false => "%b %-d, %H:%M",
+    };
+
+    format!(
+        "between {} and {} UTC with {:.0}% confidence",
+        interval.earliest.format(time_format),
+        interval.latest.format(time_format),
+        CONFIDENCE_LEVEL * 100.0
+    )
+}
+
+/// Renders the given template in strict mode, so that a variable, which is not
+/// contained in the data, results in an error instead of an empty string.
+/// All variables used in the template, that are not contained in the data, are
+/// returned in the error instead of failing at the first missing one.
+/// The values are not HTML-escaped, as the rendered proposal is Markdown.
+pub fn render_template(template: &str, data: &Value) -> Result<String, Error> {
+    let missing = get_missing_variables(template, data)?;
+    if !missing.is_empty() {
+        return Err(Error::MissingVariables(missing));
+    }
+
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(true);
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars.register_template_string("proposal", template)?;
+
+    Ok(handlebars.render("proposal", data)?)
+}
+
+/// Returns the variables used in the template, that are not defined in the given data.
+/// The template is rendered without strict mode, so that every missing variable is passed to
+/// the `helperMissing` hook, which resolves the variables in their block scope (e.g. `#each`).
+fn get_missing_variables(template: &str, data: &Value) -> Result<Vec<String>, Error> {
+    let missing: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let mut handlebars = Handlebars::new();
+    let recorded = Arc::clone(&missing);
+    handlebars.register_helper(
+        "helperMissing",
+        Box::new(
+            move |h: &Helper,
+                  _: &Handlebars,
+                  _: &Context,
+                  _: &mut RenderContext,
+                  _: &mut dyn Output|
+                  -> HelperResult {
+                let mut recorded = recorded.lock().expect("the lock should not be poisoned");
+                if !recorded.iter().any(|name| name == h.name()) {
+                    recorded.push(h.name().to_string());
+                }
+                Ok(())
+            },
+        ),
+    );
+    handlebars.register_template_string("proposal", template)?;
+    handlebars.render("proposal", data)?;
+
+    let missing = missing
+        .lock()
+        .expect("the lock should not be poisoned")
+        .clone();
+    Ok(missing)
+}
+
+/// Returns the template to use for the given chain profile from the template directory.
+/// A profile specific template (e.g. `evmos-testnet.hbs`) is preferred over a template for
+/// the network type (e.g. `testnet.hbs`), which is preferred over a shared `proposal.hbs`.
+/// If none of them exists, an error naming the tried paths is returned.
+pub fn find_template(template_dir: &Path, profile: &ChainProfile) -> Result<PathBuf, Error> {
+    let network_name = match profile.network {
+        Network::LocalNode => "local-node",
+        Network::Testnet => "testnet",
+        Network::Mainnet => "mainnet",
+    };
+
+    let candidates: Vec<PathBuf> = [
+        format!("{}.hbs", profile.name),
+        format!("{}.hbs", network_name),
+        "proposal.hbs".to_string(),
+    ]
+    .iter()
+    .map(|file_name| template_dir.join(file_name))
+    .collect();
+
+    match candidates.iter().find(|path| path.is_file()) {
+        Some(path) => Ok(path.clone()),
+        None => Err(Error::Config(format!(
+            "no template found in the template directory; tried {}",
+            candidates
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// Prepares the governance proposal JSON containing the `MsgSoftwareUpgrade` message,
+/// which can be submitted with `<daemon> tx gov submit-proposal`.
+/// The rendered proposal description is used as the summary and the given
+/// upgrade info (see [`crate::release::get_upgrade_info`]) is added to the upgrade plan.
+pub fn prepare_proposal_json(helper: &UpgradeHelper, summary: &str, info: &str) -> Value {
+    let mut proposal = json!({
+        "messages": [{
+            "@type": "/cosmos.upgrade.v1beta1.MsgSoftwareUpgrade",
+            "authority": helper.profile.gov_authority,
+            "plan": {
+                "name": helper.target_version.to_string(),
+                "height": helper.upgrade_height.to_string(),
+                "info": info,
+            },
+        }],
+        "metadata": helper.metadata,
+        "deposit": helper.deposit.as_ref().map(Coin::to_string),
+        "title": helper.proposal_name,
+        "summary": summary,
+    });
+
+    // NOTE: the expedited field is only supported by chains using Cosmos SDK v0.50+
+    if helper.expedited {
+        proposal["expedited"] = Value::Bool(true);
+    }
+
+    proposal
 }
 
-/// Writes the proposal contents to a file.
+/// Writes the proposal contents to a Markdown file in the given output directory.
 pub fn write_proposal_to_file(
     proposal: &str,
-    network: Network,
-    target_version: &str,
-) -> Result<(), std::io::Error> {
-    let proposal_file_name = format!("proposal-{}-{}.md", network, target_version);
-    std::fs::write(proposal_file_name, proposal)
+    output_dir: &Path,
+    profile_name: &str,
+    target_version: &Version,
+) -> Result<PathBuf, std::io::Error> {
+    let path = output_dir.join(get_proposal_file_name(profile_name, target_version, "md"));
+    std::fs::write(&path, proposal)?;
+
+    Ok(path)
 }
 
-/// Returns the appropriate Markdown link to the block on Mintscan for the given network and height.
-fn get_height_with_link(network: Network, height: u64) -> String {
-    let height_with_commas = height.to_formatted_string(&num_format::Locale::en);
-    match network {
-        Network::LocalNode => format!("[{}](https://www.mintscan.io/evmos/blocks/{})", height_with_commas, height),
-        Network::Mainnet => format!("[{}](https://www.mintscan.io/evmos/blocks/{})", height_with_commas, height),
-        Network::Testnet => format!("[{}](https://testnet.mintscan.io/evmos-testnet/blocks/{})", height_with_commas, height),
-    }
+/// Writes the proposal JSON to a file in the given output directory.
+pub fn write_proposal_json_to_file(
+    proposal: &Value,
+    output_dir: &Path,
+    profile_name: &str,
+    target_version: &Version,
+) -> Result<PathBuf, Error> {
+    let contents = serde_json::to_string_pretty(proposal)
+        .map_err(|e| Error::Parse(format!("proposal JSON: {}", e)))?;
+    let path = output_dir.join(get_proposal_file_name(profile_name, target_version, "json"));
+    std::fs::write(&path, contents)?;
+
+    Ok(path)
+}
+
+/// Returns the command to submit the proposal JSON at the given path with the given chain binary.
+pub fn get_submit_command(daemon: &str, proposal_path: &Path) -> String {
+    format!(
+        "{} tx gov submit-proposal {} --from <key> --gas auto --gas-adjustment 1.5",
+        daemon,
+        proposal_path.display()
+    )
+}
+
+/// Returns the file name for the proposal with the given extension.
+fn get_proposal_file_name(profile_name: &str, target_version: &Version, extension: &str) -> String {
+    format!("proposal-{}-{}.{}", profile_name, target_version, extension)
+}
+
+/// Returns the Markdown link to the block on the block explorer for the given height.
+fn get_height_with_link(profile: &ChainProfile, height: u64) -> String {
+    format!(
+        "[{}]({})",
+        height.to_formatted_string(&num_format::Locale::en),
+        profile.block_url(height)
+    )
+}
+
+/// Returns the Markdown bullet list of the given features.
+fn get_features_list(features: &[String]) -> String {
+    features
+        .iter()
+        .map(|feature| format!("- {}", feature))
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
-/// Returns the appropriate Markdown link to the release on GitHub for the given version.
-fn get_release_md_link(version: &str) -> String {
-    format!("[{0}](https://github.com/evmos/evmos/releases/tag/{0})", version)
+/// Returns the Markdown link to the release on GitHub for the given version.
+fn get_release_md_link(repository: &Repository, version: &Version) -> String {
+    format!("[{}]({})", version, repository.release_url(version))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use crate::{helper::tests::get_helper, project::Project};
+    use chrono::{TimeZone, Utc};
 
     #[tokio::test]
     async fn test_prepare_proposal_pass() {
-        let helper = UpgradeHelper::new(Network::Mainnet, "v0.0.1", "v0.1.0", Utc::now()).await;
+        let helper = UpgradeHelper::new(
+            ChainProfile::builtin(Network::Mainnet),
+            "v0.0.1".parse().unwrap(),
+            "v0.1.0".parse().unwrap(),
+            Utc::now(),
+            false,
+            Project::default(),
+            PathBuf::from("."),
+        )
+        .await
+        .expect("the helper should be created");
 
         let result = prepare_proposal(&helper);
         assert!(
@@ -78,9 +286,248 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_prepare_proposal_expedited_patch() {
+        let mut helper = get_helper("v14.0.0", "v14.0.1", 23);
+        helper.expedited = true;
+
+        let proposal = prepare_proposal(&helper).expect("the proposal should be rendered");
+        assert!(
+            proposal.contains("schedule a patch software upgrade of Evmos Local Node"),
+            "expected the upgrade type in the proposal",
+        );
+        assert!(
+            proposal.contains("hours as an expedited proposal."),
+            "expected the expedited voting in the proposal",
+        );
+    }
+
+    #[test]
+    fn test_prepare_proposal_time_interval() {
+        let mut helper = get_helper("v14.0.0", "v15.0.0", 23);
+        let proposal = prepare_proposal(&helper).expect("the proposal should be rendered");
+        assert!(
+            !proposal.contains("confidence"),
+            "expected no time interval without an estimated variance",
+        );
+
+        helper.upgrade_time_interval = Some(ConfidenceInterval {
+            earliest: Utc.with_ymd_and_hms(2023, 10, 23, 15, 42, 10).unwrap(),
+            latest: Utc.with_ymd_and_hms(2023, 10, 23, 16, 19, 50).unwrap(),
+        });
+        let proposal = prepare_proposal(&helper).expect("the proposal should be rendered");
+        assert!(
+            proposal.contains(
+                "over the last 50,000 blocks, between 15:42 and 16:19 UTC with 90% confidence)"
+            ),
+            "expected the time interval in the proposal",
+        );
+    }
+
+    #[test]
+    fn test_get_time_interval_string_multiple_days() {
+        let interval = ConfidenceInterval {
+            earliest: Utc.with_ymd_and_hms(2023, 10, 23, 22, 30, 0).unwrap(),
+            latest: Utc.with_ymd_and_hms(2023, 10, 24, 1, 30, 0).unwrap(),
+        };
+        assert_eq!(
+            get_time_interval_string(&interval),
+            "between Oct 23, 22:30 and Oct 24, 01:30 UTC with 90% confidence"
+        );
+    }
+
+    #[test]
+    fn test_prepare_proposal_custom_template() {
+        let path = std::env::temp_dir().join("upgrade-helper-custom-template.hbs");
+        std::fs::write(&path, "{{name}} at {{height}}").unwrap();
+
+        let mut helper = get_helper("v14.0.0", "v15.0.0", 23);
+        helper.template = Some(path.clone());
+
+        let proposal = prepare_proposal(&helper).expect("the proposal should be rendered");
+        assert_eq!(
+            proposal,
+            format!(
+                "{} at {}",
+                helper.proposal_name,
+                get_height_with_link(&helper.profile, helper.upgrade_height)
+            ),
+        );
+
+        // Clean up
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_render_template_missing_variables() {
+        let template = "{{name}} by {{team}}{{#each features}}{{this}} on {{chain}}{{/each}}{{#with plan}}{{name}} at {{height}}{{/with}}{{team}}";
+        let data = json!({
+            "name": "v15.0.0",
+            "features": ["a", "b"],
+            "plan": {"name": "v15.0.0"},
+        });
+        let err = render_template(template, &data).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "template uses undefined variables: team, chain, height",
+        );
+    }
+
+    #[test]
+    fn test_render_template_block_scopes() {
+        let template = "{{#each features}}{{@index}}: {{this}}\n{{/each}}{{#with plan}}{{name}} at {{height}}{{/with}}";
+        let data = json!({
+            "features": ["a", "b"],
+            "plan": {"name": "v15.0.0", "height": "100"},
+        });
+        assert_eq!(
+            render_template(template, &data).expect("the template should be rendered"),
+            "0: a\n1: b\nv15.0.0 at 100"
+        );
+    }
+
+    #[test]
+    fn test_render_template_no_escape() {
+        let feature = "Add the `vesting` precompile & fix <x> = y";
+        let rendered = render_template(
+            "{{#each features}}- {{this}}{{/each}}",
+            &json!({"features": [feature]}),
+        )
+        .expect("the template should be rendered");
+        assert_eq!(rendered, format!("- {}", feature));
+    }
+
+    #[test]
+    fn test_default_template_has_all_variables() {
+        let helper = get_helper("v14.0.0", "v15.0.0", 23);
+        assert!(
+            prepare_proposal(&helper).is_ok(),
+            "expected the embedded template to be rendered",
+        );
+    }
+
+    #[test]
+    fn test_find_template() {
+        let dir = std::env::temp_dir().join("upgrade-helper-templates");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("evmos-mainnet.hbs"), "evmos mainnet").unwrap();
+        std::fs::write(dir.join("testnet.hbs"), "testnet").unwrap();
+        std::fs::write(dir.join("proposal.hbs"), "shared").unwrap();
+
+        let mainnet = ChainProfile::builtin(Network::Mainnet);
+        let testnet = ChainProfile::builtin(Network::Testnet);
+        let local_node = ChainProfile::builtin(Network::LocalNode);
+        assert_eq!(
+            find_template(&dir, &mainnet).unwrap(),
+            dir.join("evmos-mainnet.hbs")
+        );
+        assert_eq!(
+            find_template(&dir, &testnet).unwrap(),
+            dir.join("testnet.hbs")
+        );
+        assert_eq!(
+            find_template(&dir, &local_node).unwrap(),
+            dir.join("proposal.hbs")
+        );
+
+        let err = find_template(&dir.join("missing"), &mainnet).unwrap_err();
+        assert!(
+            matches!(err, Error::Config(_))
+                && err.to_string().contains(
+                    &dir.join("missing")
+                        .join("proposal.hbs")
+                        .display()
+                        .to_string()
+                ),
+            "expected the tried paths in the error, got: {}",
+            err
+        );
+
+        // Clean up
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_prepare_proposal_json() {
+        let helper = get_helper("v14.0.0", "v15.0.0", 23);
+        let proposal = prepare_proposal_json(&helper, "summary", "{\"binaries\":{}}");
+
+        let message = &proposal["messages"][0];
+        assert_eq!(
+            message["@type"],
+            "/cosmos.upgrade.v1beta1.MsgSoftwareUpgrade"
+        );
+        assert_eq!(
+            message["authority"],
+            "evmos10d07y265gmmuvt4z0w9aw880jnsr700jcrztvm"
+        );
+        assert_eq!(message["plan"]["name"], "v15.0.0");
+        assert_eq!(message["plan"]["height"], "16800000");
+        assert_eq!(message["plan"]["info"], "{\"binaries\":{}}");
+        assert_eq!(proposal["title"], helper.proposal_name);
+        assert_eq!(proposal["summary"], "summary");
+        assert_eq!(proposal["deposit"], "2000000000000000000000aevmos");
+        assert!(proposal.get("expedited").is_none());
+    }
+
+    #[test]
+    fn test_prepare_proposal_json_expedited() {
+        let mut helper = get_helper("v14.0.0", "v14.0.1", 23);
+        helper.expedited = true;
+
+        let proposal = prepare_proposal_json(&helper, "summary", "");
+        assert_eq!(proposal["expedited"], true);
+    }
+
+    #[test]
+    fn test_write_proposal_json_to_file_pass() {
+        let dir = std::env::temp_dir();
+        let path = write_proposal_json_to_file(
+            &json!({"title": "test"}),
+            &dir,
+            "evmos-testnet",
+            &"v0.1.0-rc1".parse().unwrap(),
+        )
+        .expect("the proposal JSON should be written");
+        assert_eq!(path, dir.join("proposal-evmos-testnet-v0.1.0-rc1.json"));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let written: Value = serde_json::from_str(contents.as_str()).unwrap();
+        assert_eq!(
+            written["title"], "test",
+            "expected the written proposal JSON"
+        );
+
+        // Clean up
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_get_submit_command() {
+        assert_eq!(
+            get_submit_command("evmosd", Path::new("proposal.json")),
+            "evmosd tx gov submit-proposal proposal.json --from <key> --gas auto --gas-adjustment 1.5",
+        );
+    }
+
+    #[test]
+    fn test_get_features_list() {
+        let features = vec!["first feature".to_string(), "second feature".to_string()];
+        assert_eq!(
+            get_features_list(&features),
+            "- first feature\n- second feature",
+            "expected a different features list",
+        );
+    }
+
     #[test]
     fn test_write_proposal_to_file_pass() {
-        let result = write_proposal_to_file("test", Network::Mainnet, "v0.1.0");
+        let result = write_proposal_to_file(
+            "test",
+            Path::new("."),
+            "evmos-mainnet",
+            &"v0.1.0".parse().unwrap(),
+        );
         assert!(
             result.is_ok(),
             "Error writing proposal to file: {}",
@@ -88,9 +535,9 @@ mod tests {
         );
 
         // Check that file exists
-        let proposal_file_name = format!("proposal-{}-{}.md", Network::Mainnet, "v0.1.0");
+        let proposal_file_name = "proposal-evmos-mainnet-v0.1.0.md";
         assert!(
-            std::path::Path::new(proposal_file_name.as_str()).exists(),
+            std::path::Path::new(proposal_file_name).exists(),
             "Proposal file does not exist",
         );
 
@@ -0,0 +1,374 @@
+use crate::{
+    block::BlockSource,
+    error::Error,
+    network::Network,
+    profile::{ChainProfile, GovDefaults},
+    project::Repository,
+};
+use bech32::{ToBase32, Variant};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Address bytes of the governance module account, i.e. the first 20 bytes of `sha256("gov")`,
+/// which is the same on all Cosmos SDK chains.
+const GOV_MODULE_ADDRESS: [u8; 20] = [
+    0x7b, 0x5f, 0xe2, 0x2b, 0x54, 0x46, 0xf7, 0xc6, 0x2e, 0xa2, 0x7b, 0x8b, 0xd7, 0x1c, 0xef, 0x94,
+    0xe0, 0x3f, 0x3d, 0xf2,
+];
+
+/// Represents the subset of a `chain.json` file in the cosmos/chain-registry schema,
+/// that is needed to build a chain profile.
+#[derive(Debug, Deserialize)]
+struct Chain {
+    chain_name: String,
+    chain_id: String,
+    pretty_name: Option<String>,
+    network_type: Option<String>,
+    bech32_prefix: String,
+    daemon_name: String,
+    codebase: Codebase,
+    apis: Apis,
+    #[serde(default)]
+    explorers: Vec<Explorer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Codebase {
+    git_repo: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Apis {
+    #[serde(default)]
+    rest: Vec<Api>,
+    #[serde(default)]
+    rpc: Vec<Api>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Api {
+    address: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Explorer {
+    url: Option<String>,
+    block_page: Option<String>,
+    tx_page: Option<String>,
+    account_page: Option<String>,
+}
+
+/// Loads the chain profile of the chain with the given name from a local checkout
+/// of the chain registry. Testnets are located in the `testnets` directory of the registry.
+pub fn load_chain(registry_dir: &Path, chain_name: &str) -> Result<ChainProfile, Error> {
+    let path = [
+        registry_dir.join(chain_name),
+        registry_dir.join("testnets").join(chain_name),
+    ]
+    .into_iter()
+    .map(|dir| dir.join("chain.json"))
+    .find(|path| path.is_file())
+    .ok_or(Error::Config(format!(
+        "chain {} not found in the chain registry at {}",
+        chain_name,
+        registry_dir.display()
+    )))?;
+
+    load_chain_file(&path)
+}
+
+/// Loads the chain profile from the `chain.json` file at the given path.
+pub fn load_chain_file(path: &Path) -> Result<ChainProfile, Error> {
+    let contents = std::fs::read_to_string(path)?;
+
+    parse_chain(contents.as_str()).map_err(|e| match e {
+        Error::Config(msg) => Error::Config(format!("{}: {}", path.display(), msg)),
+        e => e,
+    })
+}
+
+/// Builds the chain profile from the given contents of a `chain.json` file.
+/// As the chain registry does not contain governance parameters, no fallback deposit is set,
+/// so that the minimum deposit must be queried from the chain or passed explicitly.
+pub fn parse_chain(contents: &str) -> Result<ChainProfile, Error> {
+    let chain: Chain = serde_json::from_str(contents).map_err(|e| Error::Config(e.to_string()))?;
+
+    let network = match chain.network_type.as_deref() {
+        Some("mainnet") | None => Network::Mainnet,
+        Some("testnet") => Network::Testnet,
+        Some(network_type) => {
+            return Err(Error::Config(format!(
+                "unsupported network type {}: expected mainnet or testnet",
+                network_type
+            )))
+        }
+    };
+    let chain_name = chain.pretty_name.unwrap_or(chain.chain_name.clone());
+    let display_name = match chain_name
+        .to_lowercase()
+        .contains(&network.to_string().to_lowercase())
+    {
+        true => chain_name.clone(),
+        false => format!("{} {}", chain_name, network),
+    };
+    let rest_endpoints: Vec<String> = chain.apis.rest.into_iter().map(|api| api.address).collect();
+    if rest_endpoints.is_empty() {
+        return Err(Error::Config("no REST endpoint found".to_string()));
+    }
+    let rpc_endpoint = chain
+        .apis
+        .rpc
+        .first()
+        .ok_or(Error::Config("no RPC endpoint found".to_string()))?
+        .address
+        .clone();
+    let explorer = chain
+        .explorers
+        .iter()
+        .find_map(get_explorer_blocks_url)
+        .ok_or(Error::Config("no block explorer found".to_string()))?;
+    let voting_period_hours = match network {
+        Network::LocalNode => 1,
+        Network::Testnet => 12,
+        Network::Mainnet => 120,
+    };
+
+    let profile = ChainProfile {
+        name: chain.chain_name,
+        network,
+        chain_name,
+        display_name,
+        chain_id: chain.chain_id,
+        rest_endpoints,
+        rpc_endpoint,
+        block_source: BlockSource::Rest,
+        explorer,
+        repository: get_github_repository(chain.codebase.git_repo.as_str())
+            .map_err(Error::Config)?,
+        daemon: chain.daemon_name,
+        gov_authority: get_gov_authority(chain.bech32_prefix.as_str()).map_err(Error::Config)?,
+        gov: GovDefaults {
+            voting_period_hours,
+            deposit: None,
+        },
+        version_policy: None,
+    };
+    profile.validate()?;
+
+    Ok(profile)
+}
+
+/// Returns the base URL of the block pages of the explorer.
+/// The block page (e.g. `https://explorer.com/chain/blocks/${height}`) is preferred.
+/// Otherwise the chain's base URL is taken from the transaction or account page
+/// (e.g. `https://explorer.com/chain/txs/${txHash}`), falling back to the explorer URL.
+fn get_explorer_blocks_url(explorer: &Explorer) -> Option<String> {
+    if let Some(base) = get_page_base(&explorer.block_page, "${height}") {
+        return Some(base);
+    }
+
+    get_page_base(&explorer.tx_page, "${txHash}")
+        .and_then(|base| get_parent_url(&base))
+        .or_else(|| {
+            get_page_base(&explorer.account_page, "${accountAddress}")
+                .and_then(|base| get_parent_url(&base))
+        })
+        .or_else(|| {
+            explorer
+                .url
+                .as_ref()
+                .map(|url| url.trim_end_matches('/').to_string())
+        })
+}
+
+/// Returns the given explorer page without its placeholder, if it ends with it.
+fn get_page_base(page: &Option<String>, placeholder: &str) -> Option<String> {
+    page.as_ref()
+        .and_then(|page| page.strip_suffix(placeholder))
+        .map(|base| base.trim_end_matches('/').to_string())
+}
+
+/// Returns the URL without its last path segment, e.g. `https://explorer.com/chain`
+/// for `https://explorer.com/chain/txs`.
+fn get_parent_url(url: &str) -> Option<String> {
+    url.rsplit_once('/')
+        .map(|(parent, _)| parent.to_string())
+        .filter(|parent| !parent.ends_with('/'))
+}
+
+/// Returns the GitHub repository from the given repository URL, e.g. `https://github.com/evmos/evmos`.
+fn get_github_repository(git_repo: &str) -> Result<Repository, String> {
+    git_repo
+        .strip_prefix("https://github.com/")
+        .map(|path| path.trim_end_matches('/').trim_end_matches(".git"))
+        .ok_or(format!(
+            "unsupported repository {}: expected a GitHub URL",
+            git_repo
+        ))?
+        .parse()
+}
+
+/// Returns the address of the governance module account with the given Bech32 prefix.
+fn get_gov_authority(bech32_prefix: &str) -> Result<String, String> {
+    bech32::encode(
+        bech32_prefix,
+        GOV_MODULE_ADDRESS.to_base32(),
+        Variant::Bech32,
+    )
+    .map_err(|e| format!("invalid Bech32 prefix {}: {}", bech32_prefix, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAIN: &str = r#"{
+        "$schema": "../../chain.schema.json",
+        "chain_name": "evmostestnet",
+        "status": "live",
+        "network_type": "testnet",
+        "pretty_name": "Evmos Testnet",
+        "chain_id": "evmos_9000-4",
+        "bech32_prefix": "evmos",
+        "daemon_name": "evmosd",
+        "node_home": "$HOME/.evmosd",
+        "codebase": {
+            "git_repo": "https://github.com/evmos/evmos",
+            "recommended_version": "v15.0.0-rc2"
+        },
+        "staking": {
+            "staking_tokens": [{"denom": "atevmos"}]
+        },
+        "apis": {
+            "rpc": [{"address": "https://tm.evmos-testnet.lava.build", "provider": "Lava"}],
+            "rest": [{"address": "https://rest.evmos-testnet.lava.build", "provider": "Lava"}]
+        },
+        "explorers": [{
+            "kind": "mintscan",
+            "url": "https://testnet.mintscan.io/evmos-testnet",
+            "tx_page": "https://testnet.mintscan.io/evmos-testnet/txs/${txHash}"
+        }]
+    }"#;
+
+    #[test]
+    fn test_parse_chain_pass() {
+        let profile = parse_chain(CHAIN).expect("the chain should be parsed");
+        assert_eq!(profile.name, "evmostestnet");
+        assert_eq!(profile.network, Network::Testnet);
+        assert_eq!(profile.display_name, "Evmos Testnet");
+        assert_eq!(profile.chain_id, "evmos_9000-4");
+        assert_eq!(
+            profile.rest_endpoints,
+            vec!["https://rest.evmos-testnet.lava.build"]
+        );
+        assert_eq!(profile.rpc_endpoint, "https://tm.evmos-testnet.lava.build");
+        assert_eq!(
+            profile.explorer,
+            "https://testnet.mintscan.io/evmos-testnet"
+        );
+        assert_eq!(profile.repository.to_string(), "evmos/evmos");
+        assert_eq!(profile.daemon, "evmosd");
+        assert_eq!(profile.gov.deposit, None);
+    }
+
+    #[test]
+    fn test_parse_chain_unsupported_network_type() {
+        let chain = CHAIN.replace(
+            r#""network_type": "testnet""#,
+            r#""network_type": "devnet""#,
+        );
+        assert_eq!(
+            parse_chain(chain.as_str()).unwrap_err().to_string(),
+            "invalid config: unsupported network type devnet: expected mainnet or testnet"
+        );
+    }
+
+    #[test]
+    fn test_parse_chain_missing_rest_endpoint() {
+        let chain = CHAIN.replace(
+            r#""rest": [{"address": "https://rest.evmos-testnet.lava.build", "provider": "Lava"}]"#,
+            r#""rest": []"#,
+        );
+        assert_eq!(
+            parse_chain(chain.as_str()).unwrap_err().to_string(),
+            "invalid config: no REST endpoint found"
+        );
+    }
+
+    #[test]
+    fn test_get_gov_authority() {
+        assert_eq!(
+            get_gov_authority("evmos").unwrap(),
+            ChainProfile::builtin(Network::Mainnet).gov_authority
+        );
+        assert_eq!(
+            get_gov_authority("cosmos").unwrap(),
+            "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn"
+        );
+    }
+
+    #[test]
+    fn test_get_github_repository() {
+        assert_eq!(
+            get_github_repository("https://github.com/cosmos/gaia.git")
+                .unwrap()
+                .to_string(),
+            "cosmos/gaia"
+        );
+        assert!(get_github_repository("https://gitlab.com/cosmos/gaia").is_err());
+    }
+
+    #[test]
+    fn test_get_explorer_blocks_url() {
+        let explorer = Explorer {
+            url: Some("https://explorer.com/chain".to_string()),
+            block_page: Some("https://explorer.com/chain/block/${height}".to_string()),
+            tx_page: Some("https://explorer.com/chain/tx/${txHash}".to_string()),
+            account_page: None,
+        };
+        assert_eq!(
+            get_explorer_blocks_url(&explorer),
+            Some("https://explorer.com/chain/block".to_string())
+        );
+
+        let explorer = Explorer {
+            url: Some("https://explorer.com".to_string()),
+            block_page: None,
+            tx_page: None,
+            account_page: Some("https://explorer.com/chain/account/${accountAddress}".to_string()),
+        };
+        assert_eq!(
+            get_explorer_blocks_url(&explorer),
+            Some("https://explorer.com/chain".to_string())
+        );
+
+        let explorer = Explorer {
+            url: Some("https://explorer.com/chain/".to_string()),
+            block_page: None,
+            tx_page: None,
+            account_page: None,
+        };
+        assert_eq!(
+            get_explorer_blocks_url(&explorer),
+            Some("https://explorer.com/chain".to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_chain() {
+        let dir = std::env::temp_dir().join("upgrade-helper-registry");
+        std::fs::create_dir_all(dir.join("testnets").join("evmostestnet")).unwrap();
+        std::fs::write(
+            dir.join("testnets").join("evmostestnet").join("chain.json"),
+            CHAIN,
+        )
+        .unwrap();
+
+        let profile = load_chain(&dir, "evmostestnet").expect("the chain should be loaded");
+        assert_eq!(profile.chain_id, "evmos_9000-4");
+        assert!(load_chain(&dir, "gaia").is_err());
+
+        // Clean up
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
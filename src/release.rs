@@ -1,48 +1,253 @@
+use crate::{error::Error, http::get, project::Repository};
 use octocrab::{models::repos::Release, Result};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use url::Url;
+
+/// Platforms for which binaries must be included in the upgrade plan.
+pub const PLATFORMS: [(&str, &str); 4] = [
+    ("linux", "amd64"),
+    ("linux", "arm64"),
+    ("darwin", "amd64"),
+    ("darwin", "arm64"),
+];
+
+/// Name of the release asset containing the SHA256 checksums of the binaries.
+const CHECKSUMS_FILE: &str = "checksums.txt";
 
 /// Sends a HTTP request to the GitHub release page and returns the response.
-pub async fn get_release(version: &str) -> Result<Release> {
+pub async fn get_release(repository: &Repository, version: &str) -> Result<Release> {
     let octocrab = octocrab::instance();
 
     octocrab
-        .repos("evmos", "evmos")
+        .repos(&repository.owner, &repository.name)
         .releases()
         .get_by_tag(version)
         .await
 }
 
+/// Returns true if the given error means that the requested release does not exist.
+pub fn is_not_found(error: &octocrab::Error) -> bool {
+    matches!(error, octocrab::Error::GitHub { source, .. } if source.status_code.as_u16() == 404)
+}
+
+/// Returns the tags of all releases on GitHub, fetching every page of the release list.
+pub async fn get_release_tags(repository: &Repository) -> Result<Vec<String>> {
+    let octocrab = octocrab::instance();
+
+    let page = octocrab
+        .repos(&repository.owner, &repository.name)
+        .releases()
+        .list()
+        .per_page(100)
+        .send()
+        .await?;
+    let releases = octocrab.all_pages(page).await?;
+
+    Ok(releases
+        .into_iter()
+        .map(|release| release.tag_name)
+        .collect())
+}
+
 /// Checks if the release for the target version already exists by
 /// sending a HTTP request to the GitHub release page.
-pub async fn check_release_exists(version: &str) -> bool {
-    match get_release(version).await {
-        Ok(_) => true,
-        _ => false,
+pub async fn check_release_exists(repository: &Repository, version: &str) -> bool {
+    get_release(repository, version).await.is_ok()
+}
+
+/// Returns the Cosmovisor-compatible upgrade plan info, which contains the download links
+/// and checksums for the binaries of all required platforms that are attached to the release.
+pub async fn get_upgrade_info(release: &Release) -> std::result::Result<Value, Error> {
+    let assets: Vec<(String, Url)> = release
+        .assets
+        .iter()
+        .map(|asset| (asset.name.clone(), asset.browser_download_url.clone()))
+        .collect();
+
+    let (_, checksums_url) = assets
+        .iter()
+        .find(|(name, _)| name == CHECKSUMS_FILE)
+        .ok_or(Error::Release(format!(
+            "{} has no {} asset",
+            release.tag_name, CHECKSUMS_FILE
+        )))?;
+    let checksums = get(checksums_url.clone())
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    build_upgrade_info(&assets, checksums.as_str())
+}
+
+/// Builds the upgrade plan info from the given release assets and the contents of the checksums file.
+/// Returns an error if any of the required platforms is missing.
+fn build_upgrade_info(
+    assets: &[(String, Url)],
+    checksums: &str,
+) -> std::result::Result<Value, Error> {
+    let checksums = parse_checksums(checksums);
+    let mut binaries = Map::new();
+
+    for (os, arch) in PLATFORMS {
+        let platform = format!("{}/{}", os, arch);
+        let (name, url) = assets
+            .iter()
+            .find(|(name, _)| {
+                let name = name.to_lowercase();
+                name.contains(format!("_{}_{}.", os, arch).as_str()) && name.ends_with(".tar.gz")
+            })
+            .ok_or(Error::Release(format!("missing binary for {}", platform)))?;
+
+        let checksum = checksums
+            .get(name.as_str())
+            .ok_or(Error::Release(format!("missing checksum for {}", name)))?;
+
+        binaries.insert(
+            platform,
+            Value::String(format!("{}?checksum=sha256:{}", url, checksum)),
+        );
     }
+
+    Ok(json!({ "binaries": binaries }))
+}
+
+/// Parses the checksums file, which contains one `<checksum>  <file name>` entry per line.
+fn parse_checksums(checksums: &str) -> HashMap<&str, &str> {
+    checksums
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(checksum), Some(name)) => Some((name, checksum)),
+                _ => None,
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const CHECKSUMS: &str = "\
+aa11  evmos_15.0.0_Darwin_amd64.tar.gz
+bb22  evmos_15.0.0_Darwin_arm64.tar.gz
+cc33  evmos_15.0.0_Linux_amd64.tar.gz
+dd44  evmos_15.0.0_Linux_arm64.tar.gz
+ee55  evmos_15.0.0_Windows_x86_64.zip
+";
+
+    fn get_assets(names: &[&str]) -> Vec<(String, Url)> {
+        names
+            .iter()
+            .map(|name| {
+                let url = format!(
+                    "https://github.com/evmos/evmos/releases/download/v15.0.0/{}",
+                    name
+                );
+                (name.to_string(), Url::parse(url.as_str()).unwrap())
+            })
+            .collect()
+    }
+
     #[tokio::test]
     async fn test_get_release_pass() {
-        let release = get_release("v14.0.0").await.unwrap();
+        let release = get_release(&Repository::default(), "v14.0.0")
+            .await
+            .unwrap();
         assert_eq!(release.tag_name, "v14.0.0");
     }
 
     #[tokio::test]
     async fn test_get_release_fail() {
-        let res = get_release("invalidj.xjaf/ie").await;
-        assert_eq!(res.is_err(), true);
+        let res = get_release(&Repository::default(), "invalidj.xjaf/ie").await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_release_not_found() {
+        let err = get_release(&Repository::default(), "v14.0.8")
+            .await
+            .expect_err("expected release not to exist");
+        assert!(
+            is_not_found(&err),
+            "expected a not found error; got: {}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_release_tags_pass() {
+        let tags = get_release_tags(&Repository::default()).await.unwrap();
+        assert!(!tags.is_empty(), "expected releases to be found");
     }
 
     #[tokio::test]
     async fn test_check_release_exists_pass() {
-        assert_eq!(check_release_exists("v14.0.0").await, true);
+        assert!(check_release_exists(&Repository::default(), "v14.0.0").await);
     }
 
     #[tokio::test]
     async fn test_check_release_exists_fail() {
-        assert_eq!(check_release_exists("v14.0.8").await, false);
+        assert!(!check_release_exists(&Repository::default(), "v14.0.8").await);
+    }
+
+    #[test]
+    fn test_build_upgrade_info_pass() {
+        let assets = get_assets(&[
+            "checksums.txt",
+            "evmos_15.0.0_Darwin_amd64.tar.gz",
+            "evmos_15.0.0_Darwin_arm64.tar.gz",
+            "evmos_15.0.0_Linux_amd64.tar.gz",
+            "evmos_15.0.0_Linux_arm64.tar.gz",
+            "evmos_15.0.0_Windows_x86_64.zip",
+        ]);
+
+        let info = build_upgrade_info(&assets, CHECKSUMS).expect("the info should be built");
+        assert_eq!(
+            info["binaries"]["linux/amd64"],
+            "https://github.com/evmos/evmos/releases/download/v15.0.0/evmos_15.0.0_Linux_amd64.tar.gz?checksum=sha256:cc33",
+        );
+        assert_eq!(
+            info["binaries"]["darwin/arm64"],
+            "https://github.com/evmos/evmos/releases/download/v15.0.0/evmos_15.0.0_Darwin_arm64.tar.gz?checksum=sha256:bb22",
+        );
+        assert_eq!(info["binaries"].as_object().unwrap().len(), PLATFORMS.len());
+    }
+
+    #[test]
+    fn test_build_upgrade_info_missing_platform() {
+        let assets = get_assets(&[
+            "checksums.txt",
+            "evmos_15.0.0_Darwin_amd64.tar.gz",
+            "evmos_15.0.0_Linux_amd64.tar.gz",
+            "evmos_15.0.0_Linux_arm64.tar.gz",
+        ]);
+
+        let err = build_upgrade_info(&assets, CHECKSUMS).unwrap_err();
+        assert!(
+            err.to_string().contains("darwin/arm64"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_build_upgrade_info_missing_checksum() {
+        let assets = get_assets(&[
+            "evmos_15.0.0_Darwin_amd64.tar.gz",
+            "evmos_15.0.0_Darwin_arm64.tar.gz",
+            "evmos_15.0.0_Linux_amd64.tar.gz",
+            "evmos_15.0.0_Linux_arm64.tar.gz",
+        ]);
+
+        let err = build_upgrade_info(&assets, "cc33  evmos_15.0.0_Linux_amd64.tar.gz").unwrap_err();
+        assert!(
+            err.to_string().contains("missing checksum"),
+            "unexpected error: {}",
+            err
+        );
     }
 }
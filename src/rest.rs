@@ -0,0 +1,227 @@
+use crate::{
+    block::{process_block_body, Block},
+    error::Error,
+    http::get as get_url,
+    profile::{get_endpoint_url, ChainProfile},
+};
+use chrono::{DateTime, Duration, Utc};
+
+/// Maximum age of the latest block, for which a REST endpoint is considered to be in sync.
+pub const MAX_BLOCK_AGE_MINUTES: i64 = 5;
+
+/// Endpoint of the latest block, whose responses must be recent to be used.
+pub(crate) const LATEST_BLOCK_ENDPOINT: &str = "cosmos/base/tendermint/v1beta1/blocks/latest";
+
+/// Queries the given endpoint of the REST API and returns the response body.
+/// The REST endpoints of the profile are tried in order, so that a failing request
+/// or an error response is retried with the next endpoint.
+/// A stale latest block is retried as well and only used if no endpoint serves a recent one.
+/// The endpoint, that served the query, is reported.
+pub(crate) async fn get(profile: &ChainProfile, endpoint: &str) -> Result<String, Error> {
+    let mut last_error: Option<Error> = None;
+    let mut stale_body: Option<String> = None;
+
+    for base_url in &profile.rest_endpoints {
+        match get_from(base_url, endpoint).await {
+            Ok(body) => match check_response_age(endpoint, body.as_str(), Utc::now()) {
+                Some(problem) => {
+                    println!("WARNING: query to {} is stale: {}", base_url, problem);
+                    stale_body.get_or_insert(body);
+                }
+                None => {
+                    println!("  {} served by {}", endpoint, base_url);
+                    return Ok(body);
+                }
+            },
+            Err(e) => {
+                println!("WARNING: query to {} failed: {}", base_url, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    if let Some(body) = stale_body {
+        println!(
+            "WARNING: no REST endpoint served a recent response to {}, using a stale one",
+            endpoint
+        );
+        return Ok(body);
+    }
+
+    Err(last_error.unwrap_or(Error::Config(format!(
+        "no REST endpoints configured for {}",
+        profile.display_name
+    ))))
+}
+
+/// Queries the given endpoint from the REST API at the given base URL.
+async fn get_from(base_url: &str, endpoint: &str) -> Result<String, Error> {
+    let url = get_endpoint_url(base_url, endpoint)?;
+    let response = get_url(url).await?.error_for_status()?;
+
+    Ok(response.text().await?)
+}
+
+/// Checks the health of all REST endpoints of the profile and returns the profile
+/// with only the healthy endpoints, keeping their order.
+/// An endpoint is healthy if its latest block belongs to the chain of the profile and is recent.
+/// If no endpoint is healthy, all endpoints are kept, so that the queries can still be tried.
+pub async fn check_endpoints(mut profile: ChainProfile) -> ChainProfile {
+    let mut healthy: Vec<String> = Vec::new();
+
+    for base_url in &profile.rest_endpoints {
+        match check_endpoint(base_url, profile.chain_id.as_str()).await {
+            Ok(height) => {
+                println!("REST endpoint {} is healthy at height {}", base_url, height);
+                healthy.push(base_url.clone());
+            }
+            Err(e) => println!("WARNING: REST endpoint {} is unhealthy: {}", base_url, e),
+        }
+    }
+
+    match healthy.is_empty() {
+        true => println!(
+            "WARNING: no healthy REST endpoint found for {}",
+            profile.display_name
+        ),
+        false => profile.rest_endpoints = healthy,
+    }
+
+    profile
+}
+
+/// Checks the latest block of the REST endpoint at the given base URL.
+/// Returns the latest block height if the endpoint is healthy.
+async fn check_endpoint(base_url: &str, chain_id: &str) -> Result<u64, String> {
+    let body = get_from(base_url, LATEST_BLOCK_ENDPOINT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    check_latest_block(body.as_str(), chain_id, Utc::now()).map_err(|problems| problems.join("; "))
+}
+
+/// Checks that the latest block in the given response body belongs to the chain
+/// with the given ID and is not older than the maximum block age.
+/// Returns the block height if the block is valid and all found problems otherwise.
+fn check_latest_block(body: &str, chain_id: &str, now: DateTime<Utc>) -> Result<u64, Vec<String>> {
+    let block = process_block_body(body).map_err(|e| vec![e.to_string()])?;
+
+    let mut problems: Vec<String> = Vec::new();
+
+    if block.chain_id != chain_id {
+        problems.push(format!(
+            "chain ID {} does not match the expected chain ID {}",
+            block.chain_id, chain_id
+        ));
+    }
+
+    problems.extend(check_block_age(&block, now));
+
+    match problems.is_empty() {
+        true => Ok(block.height),
+        false => Err(problems),
+    }
+}
+
+/// Checks that the response to the latest block endpoint is not older than the maximum block age.
+/// Returns the problem if the response is stale; other responses are not checked.
+fn check_response_age(endpoint: &str, body: &str, now: DateTime<Utc>) -> Option<String> {
+    if endpoint != LATEST_BLOCK_ENDPOINT {
+        return None;
+    }
+
+    // NOTE: invalid bodies are reported by the caller, that processes the response
+    process_block_body(body)
+        .ok()
+        .and_then(|block| check_block_age(&block, now))
+}
+
+/// Checks that the given block is not older than the maximum block age.
+fn check_block_age(block: &Block, now: DateTime<Utc>) -> Option<String> {
+    match now - block.time > Duration::minutes(MAX_BLOCK_AGE_MINUTES) {
+        true => Some(format!(
+            "latest block {} at {} is older than {} minutes",
+            block.height, block.time, MAX_BLOCK_AGE_MINUTES
+        )),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use chrono::TimeZone;
+
+    const LATEST_BLOCK: &str = r#"{"block_id":{},"block":{"header":{"chain_id":"evmos_9001-2","height":"16705125","time":"2023-10-23T15:59:58.123456789Z","proposer_address":"Y2bVwiYzbUxEEC5BUNsQAJnjm54="}}}"#;
+
+    #[test]
+    fn test_check_latest_block_pass() {
+        let now = Utc.with_ymd_and_hms(2023, 10, 23, 16, 0, 0).unwrap();
+        assert_eq!(
+            check_latest_block(LATEST_BLOCK, "evmos_9001-2", now),
+            Ok(16705125)
+        );
+    }
+
+    #[test]
+    fn test_check_latest_block_fail() {
+        let now = Utc.with_ymd_and_hms(2023, 10, 23, 17, 0, 0).unwrap();
+        let problems = check_latest_block(LATEST_BLOCK, "evmos_9000-4", now).unwrap_err();
+        assert_eq!(
+            problems.len(),
+            2,
+            "expected the chain ID and the block age to be reported"
+        );
+    }
+
+    #[test]
+    fn test_check_response_age() {
+        let now = Utc.with_ymd_and_hms(2023, 10, 23, 16, 0, 0).unwrap();
+        assert_eq!(
+            check_response_age(LATEST_BLOCK_ENDPOINT, LATEST_BLOCK, now),
+            None
+        );
+
+        let later = now + Duration::minutes(MAX_BLOCK_AGE_MINUTES + 1);
+        assert!(check_response_age(LATEST_BLOCK_ENDPOINT, LATEST_BLOCK, later).is_some());
+        assert_eq!(
+            check_response_age(
+                "cosmos/base/tendermint/v1beta1/blocks/16705125",
+                LATEST_BLOCK,
+                later
+            ),
+            None,
+            "expected only the latest block to be checked"
+        );
+    }
+
+    #[test]
+    fn test_check_latest_block_invalid_body() {
+        let now = Utc.with_ymd_and_hms(2023, 10, 23, 16, 0, 0).unwrap();
+        assert!(check_latest_block("{}", "evmos_9001-2", now).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_failover() {
+        let mut profile = ChainProfile::builtin(Network::Mainnet);
+        let rest_endpoints = profile.rest_endpoints.clone();
+        profile.rest_endpoints = vec!["https://invalidurl.org".to_string()];
+        profile.rest_endpoints.extend(rest_endpoints);
+
+        let body = get(&profile, "cosmos/base/tendermint/v1beta1/node_info")
+            .await
+            .expect("the query should be served by the next endpoint");
+        assert!(body.contains("application_version"));
+    }
+
+    #[tokio::test]
+    async fn test_get_fail() {
+        let mut profile = ChainProfile::builtin(Network::Mainnet);
+        profile.rest_endpoints = vec!["https://invalidurl.org".to_string()];
+
+        assert!(get(&profile, "cosmos/base/tendermint/v1beta1/node_info")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{error::Error, profile::ChainProfile, rest};
+use serde_json::Value;
+use std::fmt;
+
+/// Number of blocks around the planned upgrade height, in which other proposed upgrades are considered conflicting.
+pub const CONFLICT_HEIGHT_MARGIN: u64 = 10_000;
+
+/// Represents a software upgrade plan.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    pub name: String,
+    pub height: u64,
+}
+
+/// Represents an upgrade plan that is either already scheduled or proposed in a governance proposal.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PendingPlan {
+    Scheduled(Plan),
+    Proposed { proposal_id: u64, plan: Plan },
+}
+
+impl fmt::Display for PendingPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PendingPlan::Scheduled(plan) => write!(
+                f,
+                "scheduled upgrade {} at height {}",
+                plan.name, plan.height
+            ),
+            PendingPlan::Proposed { proposal_id, plan } => write!(
+                f,
+                "upgrade {} at height {} proposed in proposal #{}",
+                plan.name, plan.height, proposal_id
+            ),
+        }
+    }
+}
+
+/// Queries the currently scheduled upgrade plan from the given network.
+pub async fn get_current_plan(profile: &ChainProfile) -> Result<Option<Plan>, Error> {
+    let body = rest::get(profile, "cosmos/upgrade/v1beta1/current_plan").await?;
+
+    process_current_plan_body(body.as_str())
+}
+
+/// Queries the height at which the upgrade plan with the given name was applied on the given network.
+/// Returns `None` if no upgrade with this name was applied.
+pub async fn get_applied_plan_height(
+    profile: &ChainProfile,
+    name: &str,
+) -> Result<Option<u64>, Error> {
+    let endpoint = format!("cosmos/upgrade/v1beta1/applied_plan/{}", name);
+    let body = rest::get(profile, endpoint.as_str()).await?;
+
+    process_applied_plan_body(body.as_str())
+}
+
+/// Queries the application version, that the node of the given network is running.
+pub async fn get_node_version(profile: &ChainProfile) -> Result<String, Error> {
+    let body = rest::get(profile, "cosmos/base/tendermint/v1beta1/node_info").await?;
+
+    process_node_info_body(body.as_str())
+}
+
+/// Processes the response body of the applied plan query.
+fn process_applied_plan_body(body: &str) -> Result<Option<u64>, Error> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| Error::Parse(format!("applied plan response body: {}", e)))?;
+    let height = value["height"]
+        .as_str()
+        .and_then(|height| height.parse::<u64>().ok())
+        .ok_or(Error::Parse(format!("applied plan height: {}", value)))?;
+
+    match height {
+        0 => Ok(None),
+        height => Ok(Some(height)),
+    }
+}
+
+/// Processes the response body of the node info query.
+fn process_node_info_body(body: &str) -> Result<String, Error> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| Error::Parse(format!("node info response body: {}", e)))?;
+
+    value["application_version"]["version"]
+        .as_str()
+        .map(|version| version.to_string())
+        .ok_or(Error::Parse(
+            "application version from node info".to_string(),
+        ))
+}
+
+/// Processes the response body of the current plan query.
+fn process_current_plan_body(body: &str) -> Result<Option<Plan>, Error> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| Error::Parse(format!("current plan response body: {}", e)))?;
+
+    match &value["plan"] {
+        Value::Null => Ok(None),
+        plan => Ok(Some(parse_plan(plan)?)),
+    }
+}
+
+/// Parses the upgrade plan from its JSON representation.
+pub(crate) fn parse_plan(plan: &Value) -> Result<Plan, Error> {
+    let name = plan["name"]
+        .as_str()
+        .ok_or(Error::Parse(format!("upgrade plan name: {}", plan)))?;
+    let height = plan["height"]
+        .as_str()
+        .and_then(|height| height.parse::<u64>().ok())
+        .ok_or(Error::Parse(format!("upgrade plan height: {}", plan)))?;
+
+    Ok(Plan {
+        name: name.to_string(),
+        height,
+    })
+}
+
+/// Returns the descriptions of all pending plans, that conflict with an upgrade at the given height.
+/// A scheduled plan is always conflicting, because scheduling another upgrade would replace it.
+/// Proposed plans are conflicting if they target a height within the [`CONFLICT_HEIGHT_MARGIN`].
+pub fn find_conflicting_plans(upgrade_height: u64, pending_plans: &[PendingPlan]) -> Vec<String> {
+    pending_plans
+        .iter()
+        .filter(|pending| match pending {
+            PendingPlan::Scheduled(_) => true,
+            PendingPlan::Proposed { plan, .. } => {
+                plan.height.abs_diff(upgrade_height) <= CONFLICT_HEIGHT_MARGIN
+            }
+        })
+        .map(|pending| format!("conflicting {}", pending))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+
+    fn get_plan(height: u64) -> Plan {
+        Plan {
+            name: "v15.0.0".to_string(),
+            height,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_current_plan_mainnet() {
+        let res = get_current_plan(&ChainProfile::builtin(Network::Mainnet)).await;
+        assert!(res.is_ok(), "expected the current plan to be queried");
+    }
+
+    #[tokio::test]
+    async fn test_get_applied_plan_height_mainnet() {
+        let height = get_applied_plan_height(&ChainProfile::builtin(Network::Mainnet), "v14.0.0")
+            .await
+            .expect("the applied plan should be queried");
+        assert!(
+            height.is_some(),
+            "expected v14.0.0 to be applied on mainnet"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_node_version_mainnet() {
+        let version = get_node_version(&ChainProfile::builtin(Network::Mainnet))
+            .await
+            .expect("the node version should be queried");
+        assert!(!version.is_empty());
+    }
+
+    #[test]
+    fn test_process_applied_plan_body() {
+        assert_eq!(
+            process_applied_plan_body(r#"{"height":"16000000"}"#).unwrap(),
+            Some(16_000_000)
+        );
+        assert_eq!(
+            process_applied_plan_body(r#"{"height":"0"}"#).unwrap(),
+            None
+        );
+        assert!(process_applied_plan_body(r#"{}"#).is_err());
+    }
+
+    #[test]
+    fn test_process_node_info_body() {
+        let body = r#"{"default_node_info":{"network":"evmos_9001-2","version":"0.37.2"},"application_version":{"name":"evmos","app_name":"evmosd","version":"14.0.0","git_commit":"abc"}}"#;
+        assert_eq!(process_node_info_body(body).unwrap(), "14.0.0");
+        assert!(process_node_info_body(r#"{}"#).is_err());
+    }
+
+    #[test]
+    fn test_process_current_plan_body_none() {
+        let plan = process_current_plan_body(r#"{"plan":null}"#).unwrap();
+        assert_eq!(plan, None);
+    }
+
+    #[test]
+    fn test_process_current_plan_body_pass() {
+        let body = r#"{"plan":{"name":"v15.0.0","time":"0001-01-01T00:00:00Z","height":"16800000","info":"","upgraded_client_state":null}}"#;
+        let plan = process_current_plan_body(body).unwrap();
+        assert_eq!(plan, Some(get_plan(16_800_000)));
+    }
+
+    #[test]
+    fn test_process_current_plan_body_fail() {
+        let res = process_current_plan_body(r#"{"plan":{"name":"v15.0.0"}}"#);
+        assert!(res.is_err(), "expected an error for a missing plan height");
+    }
+
+    #[test]
+    fn test_find_conflicting_plans() {
+        let pending = vec![
+            PendingPlan::Proposed {
+                proposal_id: 1,
+                plan: get_plan(100_000),
+            },
+            PendingPlan::Proposed {
+                proposal_id: 2,
+                plan: get_plan(16_805_000),
+            },
+        ];
+        let conflicts = find_conflicting_plans(16_800_000, &pending);
+        assert_eq!(
+            conflicts,
+            vec!["conflicting upgrade v15.0.0 at height 16805000 proposed in proposal #2"],
+        );
+    }
+
+    #[test]
+    fn test_find_conflicting_plans_scheduled() {
+        let pending = vec![PendingPlan::Scheduled(get_plan(100_000))];
+        let conflicts = find_conflicting_plans(16_800_000, &pending);
+        assert_eq!(
+            conflicts,
+            vec!["conflicting scheduled upgrade v15.0.0 at height 100000"]
+        );
+    }
+
+    #[test]
+    fn test_find_conflicting_plans_none() {
+        assert!(find_conflicting_plans(16_800_000, &[]).is_empty());
+    }
+}
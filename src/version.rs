@@ -1,109 +1,443 @@
 use crate::network::Network;
-use regex::Regex;
+use serde::Deserialize;
+use std::{cmp::Ordering, fmt, str::FromStr};
 
-/// Returns a boolean value if the defined version fulfills the semantic
-/// versioning requirements.
-pub fn is_valid_version(version: &str) -> bool {
-    let is_valid = Regex::new(r"^v\d+\.\d+\.\d+(-rc\d+)*$")
-        .unwrap()
-        .is_match(version);
+/// Represents a semantic version in the format `vX.Y.Z` with an optional
+/// release candidate suffix `-rcN`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub rc: Option<u64>,
+}
+
+impl Version {
+    /// Returns a boolean value if the version is a release candidate.
+    pub fn is_release_candidate(&self) -> bool {
+        self.rc.is_some()
+    }
+}
 
-    if is_valid {
-        true
-    } else {
-        false
+impl Ord for Version {
+    /// Compares the versions by their major, minor and patch numbers.
+    /// A release candidate precedes the corresponding release.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then(match (self.rc, other.rc) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(rc), Some(other_rc)) => rc.cmp(&other_rc),
+            })
     }
 }
 
-/// Returns a boolean value if the defined target version fits
-/// the requirements for the selected network type.
-/// The target version must be in the format `vX.Y.Z`.
-/// Testnet upgrades must use a release candidate with the suffix `-rcX`.
-pub fn is_valid_target_version(network: Network, target_version: &str) -> bool {
-    let re: Regex;
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "v{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(rc) = self.rc {
+            write!(f, "-rc{}", rc)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    /// Parses the version from the given string, e.g. `v14.0.0` or `v14.0.0-rc1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| format!("invalid version {}: {}", s, reason);
+
+        let version = s.strip_prefix('v').ok_or(invalid("must start with `v`"))?;
+        let (numbers, rc) = match version.split_once('-') {
+            Some((numbers, suffix)) => {
+                let rc = suffix
+                    .strip_prefix("rc")
+                    .and_then(parse_number)
+                    .ok_or(invalid("suffix must be in the format `-rcN`"))?;
+                (numbers, Some(rc))
+            }
+            None => (version, None),
+        };
+
+        let parsed_numbers = numbers
+            .split('.')
+            .map(parse_number)
+            .collect::<Option<Vec<u64>>>()
+            .ok_or(invalid("version numbers must be non-negative integers"))?;
+
+        match parsed_numbers.as_slice() {
+            [major, minor, patch] => Ok(Version {
+                major: *major,
+                minor: *minor,
+                patch: *patch,
+                rc,
+            }),
+            _ => Err(invalid("expected the format `vX.Y.Z`")),
+        }
+    }
+}
+
+/// Parses a version number, which must consist of ASCII digits without leading zeros.
+fn parse_number(number: &str) -> Option<u64> {
+    let is_digits = !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits || (number.len() > 1 && number.starts_with('0')) {
+        return None;
+    }
+
+    number.parse().ok()
+}
+
+impl TryFrom<String> for Version {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Represents the type of an upgrade depending on the version numbers that change.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UpgradeType {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl UpgradeType {
+    /// Classifies the upgrade from the previous to the target version.
+    pub fn from_versions(previous_version: &Version, target_version: &Version) -> UpgradeType {
+        if target_version.major != previous_version.major {
+            UpgradeType::Major
+        } else if target_version.minor != previous_version.minor {
+            UpgradeType::Minor
+        } else {
+            UpgradeType::Patch
+        }
+    }
+}
 
-    match network {
-        Network::LocalNode => {
-            re = Regex::new(r"^v\d+\.\d{1}\.\d+(-rc\d+)*$").unwrap();
+impl fmt::Display for UpgradeType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UpgradeType::Major => write!(f, "major"),
+            UpgradeType::Minor => write!(f, "minor"),
+            UpgradeType::Patch => write!(f, "patch"),
         }
-        Network::Testnet => {
-            re = Regex::new(r"^v\d+\.\d{1}\.\d+-rc\d+$").unwrap();
+    }
+}
+
+/// Checks that the upgrade from the previous to the target version follows the upgrade policy.
+/// The target version must be newer than the previous version and no major version can be skipped.
+/// Returns the descriptions of all violations.
+pub fn check_upgrade_path(previous_version: &Version, target_version: &Version) -> Vec<String> {
+    let mut problems: Vec<String> = Vec::new();
+
+    if target_version <= previous_version {
+        problems.push(format!(
+            "target version {} must be greater than the previous version {}",
+            target_version, previous_version
+        ));
+    }
+
+    if target_version.major > previous_version.major + 1 {
+        problems.push(format!(
+            "upgrade from {} to {} skips the major version v{}",
+            previous_version,
+            target_version,
+            previous_version.major + 1
+        ));
+    }
+
+    problems
+}
+
+/// Checks if a release candidate for the given target version is contained in the given release tags.
+pub fn has_release_candidate(target_version: &Version, release_tags: &[String]) -> bool {
+    release_tags
+        .iter()
+        .filter_map(|tag| tag.parse::<Version>().ok())
+        .any(|version| {
+            version.is_release_candidate()
+                && (version.major, version.minor, version.patch)
+                    == (
+                        target_version.major,
+                        target_version.minor,
+                        target_version.patch,
+                    )
+        })
+}
+
+/// Defines whether target versions must be release candidates.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseCandidatePolicy {
+    Required,
+    Forbidden,
+    Allowed,
+}
+
+/// Requirements for the target versions of a network.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct VersionPolicy {
+    pub release_candidate: ReleaseCandidatePolicy,
+    /// Highest allowed minor version, if the minor versions are limited.
+    #[serde(default)]
+    pub max_minor: Option<u64>,
+    /// Whether a release candidate of the target version must have been released
+    /// for testing before the upgrade.
+    #[serde(default)]
+    pub require_tested_release_candidate: bool,
+}
+
+impl VersionPolicy {
+    /// Returns the default policy for the given network type.
+    /// Testnet upgrades must use a release candidate with the suffix `-rcX`,
+    /// while mainnet upgrades must use a final release, that was tested as a release candidate.
+    pub fn for_network(network: Network) -> VersionPolicy {
+        let (release_candidate, require_tested_release_candidate) = match network {
+            Network::LocalNode => (ReleaseCandidatePolicy::Allowed, false),
+            Network::Testnet => (ReleaseCandidatePolicy::Required, false),
+            Network::Mainnet => (ReleaseCandidatePolicy::Forbidden, true),
+        };
+
+        VersionPolicy {
+            release_candidate,
+            max_minor: None,
+            require_tested_release_candidate,
         }
-        Network::Mainnet => {
-            re = Regex::new(r"^v\d+\.\d{1}\.\d+$").unwrap();
+    }
+}
+
+/// Returns a boolean value if the defined target version fits the given version policy.
+pub fn is_valid_target_version(policy: &VersionPolicy, target_version: &Version) -> bool {
+    if let Some(max_minor) = policy.max_minor {
+        if target_version.minor > max_minor {
+            return false;
         }
     }
 
-    let valid_version = re.is_match(target_version);
-    if valid_version {
-        true
-    } else {
-        false
+    match policy.release_candidate {
+        ReleaseCandidatePolicy::Allowed => true,
+        ReleaseCandidatePolicy::Required => target_version.is_release_candidate(),
+        ReleaseCandidatePolicy::Forbidden => !target_version.is_release_candidate(),
     }
 }
 
+/// Checks if the given version is equal to the application version reported by a node,
+/// which is not necessarily prefixed with `v`.
+pub fn is_same_version(version: &Version, node_version: &str) -> bool {
+    version.to_string().trim_start_matches('v') == node_version.trim_start_matches('v')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::network;
+    use crate::{network::Network, profile::ChainProfile};
 
-    #[test]
-    fn test_is_valid_version_pass() {
-        assert_eq!(is_valid_version("v14.0.0"), true);
-        assert_eq!(is_valid_version("v14.0.0-rc1"), true);
+    fn policy(network: Network) -> VersionPolicy {
+        ChainProfile::builtin(network).version_policy()
     }
 
-    #[test]
-    fn test_is_valid_version_fail() {
-        assert_eq!(is_valid_version("v14.0."), false);
-        assert_eq!(is_valid_version("v.0.1"), false);
+    fn version(version: &str) -> Version {
+        version.parse().unwrap()
     }
 
     #[test]
-    fn test_is_valid_target_version_local_node_pass() {
+    fn test_parse_version_pass() {
         assert_eq!(
-            is_valid_target_version(network::Network::LocalNode, "v14.0.0",),
-            true
+            version("v14.0.0"),
+            Version {
+                major: 14,
+                minor: 0,
+                patch: 0,
+                rc: None,
+            }
+        );
+        assert_eq!(
+            version("v14.1.2-rc3"),
+            Version {
+                major: 14,
+                minor: 1,
+                patch: 2,
+                rc: Some(3),
+            }
         );
     }
 
     #[test]
-    fn test_is_valid_target_version_local_node_fail() {
+    fn test_parse_version_fail() {
+        assert_eq!(
+            "v14.0.".parse::<Version>().unwrap_err(),
+            "invalid version v14.0.: version numbers must be non-negative integers",
+        );
+        assert_eq!(
+            "v14.0".parse::<Version>().unwrap_err(),
+            "invalid version v14.0: expected the format `vX.Y.Z`",
+        );
         assert_eq!(
-            is_valid_target_version(network::Network::LocalNode, "v14.0",),
-            false
+            "14.0.0".parse::<Version>().unwrap_err(),
+            "invalid version 14.0.0: must start with `v`",
         );
+        assert_eq!(
+            "v14.0.0-beta1".parse::<Version>().unwrap_err(),
+            "invalid version v14.0.0-beta1: suffix must be in the format `-rcN`",
+        );
+        assert!("v.0.1".parse::<Version>().is_err());
+        assert_eq!(
+            "v14.+1.0".parse::<Version>().unwrap_err(),
+            "invalid version v14.+1.0: version numbers must be non-negative integers",
+        );
+        assert_eq!(
+            "v14.01.0".parse::<Version>().unwrap_err(),
+            "invalid version v14.01.0: version numbers must be non-negative integers",
+        );
+        assert!("v14.0.0-rc+1".parse::<Version>().is_err());
+        assert!("v14.0.0-rc01".parse::<Version>().is_err());
+        assert!("v14.0.0-rc".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn test_display_version() {
+        assert_eq!(version("v14.0.0").to_string(), "v14.0.0");
+        assert_eq!(version("v14.0.0-rc1").to_string(), "v14.0.0-rc1");
+    }
+
+    #[test]
+    fn test_version_ordering() {
+        assert!(version("v14.0.0") < version("v15.0.0"));
+        assert!(version("v14.0.0") < version("v14.1.0"));
+        assert!(version("v14.0.0") < version("v14.0.1"));
+        assert!(version("v14.0.0-rc1") < version("v14.0.0-rc2"));
+        assert!(version("v14.0.0-rc2") < version("v14.0.0"));
+        assert!(version("v13.9.9") < version("v14.0.0-rc1"));
+    }
+
+    #[test]
+    fn test_is_valid_target_version_local_node_pass() {
+        assert!(is_valid_target_version(
+            &policy(Network::LocalNode),
+            &version("v14.0.0")
+        ));
+    }
+
+    #[test]
+    fn test_is_valid_target_version_local_node_fail() {
+        assert!(!is_valid_target_version(
+            &policy(Network::LocalNode),
+            &version("v14.10.0")
+        ));
     }
 
     #[test]
     fn test_is_valid_target_version_testnet_pass() {
-        assert_eq!(
-            is_valid_target_version(network::Network::Testnet, "v14.0.0-rc1",),
-            true
-        );
+        assert!(is_valid_target_version(
+            &policy(Network::Testnet),
+            &version("v14.0.0-rc1")
+        ));
     }
 
     #[test]
     fn test_is_valid_target_version_testnet_fail() {
+        assert!(!is_valid_target_version(
+            &policy(Network::Testnet),
+            &version("v14.0.0")
+        ));
+    }
+
+    #[test]
+    fn test_is_valid_target_version_mainnet_pass() {
+        assert!(is_valid_target_version(
+            &policy(Network::Mainnet),
+            &version("v14.0.0")
+        ));
+    }
+
+    #[test]
+    fn test_is_valid_target_version_mainnet_fail() {
+        assert!(!is_valid_target_version(
+            &policy(Network::Mainnet),
+            &version("v14.0.0-rc1")
+        ));
+    }
+
+    #[test]
+    fn test_is_valid_target_version_without_max_minor() {
+        assert!(is_valid_target_version(
+            &VersionPolicy::for_network(Network::Mainnet),
+            &version("v14.10.0")
+        ));
+    }
+
+    #[test]
+    fn test_is_same_version() {
+        assert!(is_same_version(&version("v14.0.0"), "14.0.0"));
+        assert!(is_same_version(&version("v14.0.0-rc1"), "v14.0.0-rc1"));
+        assert!(!is_same_version(&version("v14.0.0"), "14.0.1"));
+    }
+
+    #[test]
+    fn test_upgrade_type_from_versions() {
+        assert_eq!(
+            UpgradeType::from_versions(&version("v14.0.0"), &version("v15.0.0")),
+            UpgradeType::Major
+        );
         assert_eq!(
-            is_valid_target_version(network::Network::Testnet, "v14.0.0",),
-            false
+            UpgradeType::from_versions(&version("v14.0.0"), &version("v14.1.0")),
+            UpgradeType::Minor
+        );
+        assert_eq!(
+            UpgradeType::from_versions(&version("v14.0.0"), &version("v14.0.1-rc1")),
+            UpgradeType::Patch
         );
     }
 
     #[test]
-    fn test_is_valid_target_version_mainnet_pass() {
+    fn test_check_upgrade_path_pass() {
+        assert!(check_upgrade_path(&version("v14.0.0"), &version("v15.0.0")).is_empty());
+        assert!(check_upgrade_path(&version("v15.0.0-rc1"), &version("v15.0.0-rc2")).is_empty());
+    }
+
+    #[test]
+    fn test_check_upgrade_path_downgrade() {
+        let problems = check_upgrade_path(&version("v14.0.0"), &version("v13.0.0"));
         assert_eq!(
-            is_valid_target_version(network::Network::Mainnet, "v14.0.0",),
-            true
+            problems,
+            vec!["target version v13.0.0 must be greater than the previous version v14.0.0"],
         );
     }
 
     #[test]
-    fn test_is_valid_target_version_mainnet_fail() {
+    fn test_check_upgrade_path_skipped_major() {
+        let problems = check_upgrade_path(&version("v13.0.0"), &version("v15.0.0"));
         assert_eq!(
-            is_valid_target_version(network::Network::Mainnet, "v14.0.0-rc1",),
-            false
+            problems,
+            vec!["upgrade from v13.0.0 to v15.0.0 skips the major version v14"],
         );
     }
+
+    #[test]
+    fn test_has_release_candidate() {
+        let tags = vec![
+            "v14.0.0".to_string(),
+            "v15.0.0-rc1".to_string(),
+            "v15.0.0-rc2".to_string(),
+            "invalid".to_string(),
+        ];
+        assert!(has_release_candidate(&version("v15.0.0"), &tags));
+        assert!(!has_release_candidate(&version("v14.0.0"), &tags));
+        assert!(!has_release_candidate(&version("v16.0.0"), &tags));
+    }
 }
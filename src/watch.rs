@@ -0,0 +1,190 @@
+use crate::{
+    block::{self, Block},
+    error::Error,
+    profile::ChainProfile,
+    rest,
+};
+use chrono::{DateTime, Duration, Utc};
+
+/// Periodically re-estimates the time, at which the given upgrade height is reached,
+/// and prints the deviation from the announced upgrade time.
+/// Returns an error as soon as the deviation exceeds the given maximum drift,
+/// so that a correction can be posted to the validators.
+/// Failed queries are retried at the next check, for which the health of all REST endpoints
+/// of the profile is checked again, while other errors end the monitoring.
+/// The monitoring ends once the upgrade height is reached or expected to be reached before the next check.
+pub async fn watch(
+    profile: ChainProfile,
+    height: u64,
+    announced_time: DateTime<Utc>,
+    max_drift: Duration,
+    interval: Duration,
+) -> Result<(), Error> {
+    let mut checked_profile = rest::check_endpoints(profile.clone()).await;
+
+    loop {
+        let failed = match check_height(
+            &checked_profile,
+            height,
+            announced_time,
+            max_drift,
+            interval,
+        )
+        .await
+        {
+            Ok(true) => return Ok(()),
+            Ok(false) => false,
+            Err(e) if is_query_error(&e) => {
+                println!(
+                    "WARNING: failed to estimate the time of block {}, retrying in {} minutes: {}",
+                    height,
+                    interval.num_minutes(),
+                    e
+                );
+                true
+            }
+            Err(e) => return Err(e),
+        };
+
+        tokio::time::sleep(interval.to_std().unwrap_or_default()).await;
+
+        // NOTE: all endpoints are checked again after a failure, so that the next check uses the healthy ones
+        if failed {
+            checked_profile = rest::check_endpoints(profile.clone()).await;
+        }
+    }
+}
+
+/// Estimates the time, at which the given height is reached, and checks the drift from the announced time.
+/// Returns true if the monitoring is finished, because the height is reached or expected
+/// to be reached before the next check.
+async fn check_height(
+    profile: &ChainProfile,
+    height: u64,
+    announced_time: DateTime<Utc>,
+    max_drift: Duration,
+    interval: Duration,
+) -> Result<bool, Error> {
+    let latest = block::get_latest_block(profile).await?;
+    if let Some(message) = check_reached(&latest, height) {
+        println!("{}", message);
+        return Ok(true);
+    }
+
+    let estimate = block::get_estimated_time(profile, height).await?;
+    let drift = estimate.time - announced_time;
+    println!(
+        "[{}] block {} is estimated to be reached at {} ({} from the announced time {})",
+        Utc::now().format("%Y-%m-%d %H:%M:%S"),
+        height,
+        estimate.time.format("%Y-%m-%d %H:%M:%S UTC"),
+        format_drift(drift),
+        announced_time.format("%Y-%m-%d %H:%M:%S UTC"),
+    );
+
+    check_drift(drift, max_drift)?;
+
+    if estimate.time <= Utc::now() + interval {
+        println!(
+            "Block {} is expected to be reached before the next check",
+            height
+        );
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+/// Returns true if the error is caused by a failed query, which is retried at the next check.
+fn is_query_error(error: &Error) -> bool {
+    matches!(error, Error::Network(_) | Error::Parse(_))
+}
+
+/// Returns the message to end the monitoring with, if the given latest block has reached the height.
+fn check_reached(latest: &Block, height: u64) -> Option<String> {
+    match latest.height >= height {
+        true => Some(format!(
+            "Block {} has been reached; the latest block is {} at {}",
+            height,
+            latest.height,
+            latest.time.format("%Y-%m-%d %H:%M:%S UTC")
+        )),
+        false => None,
+    }
+}
+
+/// Checks that the deviation from the announced upgrade time does not exceed the maximum drift.
+fn check_drift(drift: Duration, max_drift: Duration) -> Result<(), Error> {
+    match drift.num_seconds().abs() > max_drift.num_seconds() {
+        true => Err(Error::Drift(format!(
+            "{} from the announced time exceeds the maximum drift of {} minutes",
+            format_drift(drift),
+            max_drift.num_minutes()
+        ))),
+        false => Ok(()),
+    }
+}
+
+/// Formats the deviation from the announced upgrade time, e.g. `+3m 12s`.
+fn format_drift(drift: Duration) -> String {
+    let sign = if drift < Duration::zero() { "-" } else { "+" };
+    let seconds = drift.num_seconds().abs();
+
+    format!("{}{}m {}s", sign, seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_format_drift() {
+        assert_eq!(format_drift(Duration::seconds(192)), "+3m 12s");
+        assert_eq!(format_drift(Duration::seconds(-45)), "-0m 45s");
+        assert_eq!(format_drift(Duration::zero()), "+0m 0s");
+    }
+
+    #[test]
+    fn test_check_reached() {
+        let latest = Block {
+            height: 17_000_000,
+            time: Utc.with_ymd_and_hms(2023, 11, 6, 16, 0, 0).unwrap(),
+            chain_id: "evmos_9001-2".to_string(),
+            proposer: None,
+        };
+        assert_eq!(check_reached(&latest, 17_000_001), None);
+        assert_eq!(
+            check_reached(&latest, 17_000_000).as_deref(),
+            Some("Block 17000000 has been reached; the latest block is 17000000 at 2023-11-06 16:00:00 UTC")
+        );
+        assert!(
+            check_reached(&latest, 16_000_000).is_some(),
+            "expected a height in the past to end the monitoring"
+        );
+    }
+
+    #[test]
+    fn test_is_query_error() {
+        assert!(is_query_error(&Error::Parse("block height".to_string())));
+        assert!(!is_query_error(&Error::Estimation(
+            "block height 1 is not after the latest block height 2".to_string()
+        )));
+        assert!(!is_query_error(&Error::Drift("+31m 0s".to_string())));
+    }
+
+    #[test]
+    fn test_check_drift_pass() {
+        assert!(check_drift(Duration::minutes(-29), Duration::minutes(30)).is_ok());
+        assert!(check_drift(Duration::minutes(30), Duration::minutes(30)).is_ok());
+    }
+
+    #[test]
+    fn test_check_drift_fail() {
+        let err = check_drift(Duration::seconds(-1900), Duration::minutes(30)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "upgrade time drifted: -31m 40s from the announced time exceeds the maximum drift of 30 minutes"
+        );
+    }
+}